@@ -0,0 +1,303 @@
+use std::fmt::Display;
+
+use crate::{
+    hashing::{FastHashMap, FastHashSet},
+    Cell, Dir, Matrix, Point, CARDINALS, ORDINALS,
+};
+
+/// An unbounded grid that only stores its live cells.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SparseGrid {
+    pub live: FastHashSet<Point<i64>>,
+}
+
+impl SparseGrid {
+    /// Collects the points of all cells of `matrix` for which `is_live` holds.
+    pub fn from_matrix<T>(matrix: &Matrix<T>, is_live: impl Fn(&T) -> bool) -> Self {
+        let live = matrix
+            .items_ref()
+            .filter(|c| is_live(c.val))
+            .map(|c| Point {
+                x: c.point.x as i64,
+                y: c.point.y as i64,
+            })
+            .collect();
+        Self { live }
+    }
+
+    pub fn contains(&self, point: &Point<i64>) -> bool {
+        self.live.contains(point)
+    }
+
+    /// Number of live cells.
+    pub fn len(&self) -> usize {
+        self.live.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.live.is_empty()
+    }
+
+    /// Top-left and bottom-right corners of the smallest rectangle holding every live cell.
+    pub fn bounds(&self) -> Option<(Point<i64>, Point<i64>)> {
+        let min_x = self.live.iter().map(|p| p.x).min()?;
+        let max_x = self.live.iter().map(|p| p.x).max()?;
+        let min_y = self.live.iter().map(|p| p.y).min()?;
+        let max_y = self.live.iter().map(|p| p.y).max()?;
+        Some((Point { x: min_x, y: min_y }, Point { x: max_x, y: max_y }))
+    }
+}
+
+impl Display for SparseGrid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Some((min, max)) = self.bounds() else {
+            return Ok(());
+        };
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                let c = if self.contains(&Point { x, y }) {
+                    '#'
+                } else {
+                    '.'
+                };
+                write!(f, "{c}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Steps a grid through generations of a rule that computes every cell from its old state and
+/// its neighbors. The next generation is written into a second buffer, so rules always see the
+/// previous generation in full.
+///
+/// Works on a [`Matrix`], where the rule maps a cell and its neighbors to the new value, and on a
+/// [`SparseGrid`], where the rule decides from a cell's liveness and its live neighbor count.
+pub struct CellularAutomaton<G, R> {
+    current: G,
+    next: G,
+    rule: R,
+    include_ordinals: bool,
+    generation: usize,
+}
+
+impl<G, R> CellularAutomaton<G, R> {
+    pub fn grid(&self) -> &G {
+        &self.current
+    }
+
+    pub fn into_grid(self) -> G {
+        self.current
+    }
+
+    /// Number of steps taken so far.
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    fn directions(&self) -> Vec<Dir> {
+        let mut directions = Vec::from(CARDINALS);
+        if self.include_ordinals {
+            directions.extend(ORDINALS);
+        }
+        directions
+    }
+}
+
+impl<T, R> CellularAutomaton<Matrix<T>, R>
+where
+    T: Clone + PartialEq,
+    R: Fn(Cell<&T>, &[(Dir, Option<Cell<&T>>)]) -> T,
+{
+    pub fn new(grid: Matrix<T>, include_ordinals: bool, rule: R) -> Self {
+        Self {
+            next: grid.clone(),
+            current: grid,
+            rule,
+            include_ordinals,
+            generation: 0,
+        }
+    }
+
+    /// Advances one generation. Returns whether any cell changed.
+    pub fn step(&mut self) -> bool {
+        let mut changed = false;
+        let mut neighbors = vec![];
+        for cell in self.current.items_ref() {
+            neighbors.clear();
+            neighbors.extend(
+                self.current
+                    .all_neighbors_ref(cell.point, self.include_ordinals),
+            );
+            let point = cell.point;
+            let val = (self.rule)(cell, &neighbors);
+            changed |= val != self.current.cells[point.y][point.x];
+            self.next.cells[point.y][point.x] = val;
+        }
+        std::mem::swap(&mut self.current, &mut self.next);
+        self.generation += 1;
+        changed
+    }
+
+    pub fn run(&mut self, generations: usize) {
+        for _ in 0..generations {
+            self.step();
+        }
+    }
+
+    /// Steps until a generation changes nothing, giving up after `max_generations`.
+    /// Returns the generation at which the grid became stable.
+    pub fn run_until_stable(&mut self, max_generations: usize) -> Option<usize> {
+        for _ in 0..max_generations {
+            if !self.step() {
+                return Some(self.generation - 1);
+            }
+        }
+        None
+    }
+}
+
+impl<R> CellularAutomaton<SparseGrid, R>
+where
+    R: Fn(bool, usize) -> bool,
+{
+    pub fn new(grid: SparseGrid, include_ordinals: bool, rule: R) -> Self {
+        Self {
+            current: grid,
+            next: SparseGrid::default(),
+            rule,
+            include_ordinals,
+            generation: 0,
+        }
+    }
+
+    /// Advances one generation. Only live cells and their neighbors are considered, so the rule
+    /// must keep dead cells without live neighbors dead. Returns whether any cell changed.
+    pub fn step(&mut self) -> bool {
+        let directions = self.directions();
+        let mut counts = FastHashMap::<Point<i64>, usize>::default();
+        for point in &self.current.live {
+            counts.entry(*point).or_default();
+            for dir in &directions {
+                *counts.entry(point.moved(dir, &1)).or_default() += 1;
+            }
+        }
+
+        self.next.live.clear();
+        self.next.live.extend(
+            counts
+                .into_iter()
+                .filter(|(point, count)| (self.rule)(self.current.contains(point), *count))
+                .map(|(point, _)| point),
+        );
+
+        let changed = self.next != self.current;
+        std::mem::swap(&mut self.current, &mut self.next);
+        self.generation += 1;
+        changed
+    }
+
+    pub fn run(&mut self, generations: usize) {
+        for _ in 0..generations {
+            self.step();
+        }
+    }
+
+    /// Steps until a generation changes nothing, giving up after `max_generations`.
+    /// Returns the generation at which the grid became stable.
+    pub fn run_until_stable(&mut self, max_generations: usize) -> Option<usize> {
+        for _ in 0..max_generations {
+            if !self.step() {
+                return Some(self.generation - 1);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn life(alive: bool, neighbors: usize) -> bool {
+        neighbors == 3 || (alive && neighbors == 2)
+    }
+
+    #[test]
+    fn steps_matrices() {
+        // lights from 2015 day 18
+        let grid =
+            Matrix::<char>::try_from(".#.#.#\n...##.\n#....#\n..#...\n#.#..#\n####..").unwrap();
+        let mut automaton = CellularAutomaton::<Matrix, _>::new(grid, true, |cell, neighbors| {
+            let on = neighbors
+                .iter()
+                .filter(|(_, n)| n.is_some_and(|n| *n.val == '#'))
+                .count();
+            if life(*cell.val == '#', on) {
+                '#'
+            } else {
+                '.'
+            }
+        });
+        automaton.run(4);
+        assert_eq!(automaton.generation(), 4);
+        assert_eq!(automaton.grid().items().filter(|c| c.val == '#').count(), 4);
+    }
+
+    #[test]
+    fn detects_stable_matrices() {
+        // seating system from 2020 day 11
+        let grid = Matrix::<char>::try_from(
+            "L.LL.LL.LL\nLLLLLLL.LL\nL.L.L..L..\nLLLL.LL.LL\nL.LL.LL.LL\n\
+             L.LLLLL.LL\n..L.L.....\nLLLLLLLLLL\nL.LLLLLL.L\nL.LLLLL.LL",
+        )
+        .unwrap();
+        let mut automaton = CellularAutomaton::<Matrix, _>::new(grid, true, |cell, neighbors| {
+            let occupied = neighbors
+                .iter()
+                .filter(|(_, n)| n.is_some_and(|n| *n.val == '#'))
+                .count();
+            match *cell.val {
+                'L' if occupied == 0 => '#',
+                '#' if occupied >= 4 => 'L',
+                val => val,
+            }
+        });
+        assert_eq!(automaton.run_until_stable(100), Some(5));
+        let grid = automaton.into_grid();
+        assert_eq!(grid.items().filter(|c| c.val == '#').count(), 37);
+
+        // cells that aren't `Copy`: every word grows to the longest word next to it
+        let words = Matrix::from_fn(3, 1, |p| "ab".repeat(p.x));
+        let mut automaton = CellularAutomaton::<Matrix<String>, _>::new(words, false, |cell, n| {
+            n.iter()
+                .filter_map(|(_, n)| n.map(|n| n.val))
+                .chain([cell.val])
+                .max_by_key(|w| w.len())
+                .unwrap()
+                .clone()
+        });
+        assert_eq!(automaton.run_until_stable(10), Some(2));
+        assert_eq!(
+            automaton.grid().get_ref(0, 0).map(String::as_str),
+            Some("abab")
+        );
+    }
+
+    #[test]
+    fn steps_sparse_grids() {
+        let glider = Matrix::<char>::try_from(".#.\n..#\n###").unwrap();
+        let grid = SparseGrid::from_matrix(&glider, |c| *c == '#');
+        let mut automaton = CellularAutomaton::<SparseGrid, _>::new(grid.clone(), true, life);
+        automaton.run(4);
+        let shifted = grid.live.iter().map(|p| p.moved(&Dir::SE, &1)).collect();
+        assert_eq!(automaton.grid().live, shifted);
+
+        let block =
+            SparseGrid::from_matrix(&Matrix::<char>::try_from("##\n##").unwrap(), |c| *c == '#');
+        let mut automaton = CellularAutomaton::<SparseGrid, _>::new(block, true, life);
+        assert_eq!(automaton.run_until_stable(10), Some(0));
+        assert_eq!(automaton.grid().to_string(), "##\n##\n");
+    }
+}
@@ -0,0 +1,96 @@
+use crate::math::{concat, strip_suffix, Integer};
+
+/// A binary operator that can be undone: given the result and the right-hand operand,
+/// `invert` recovers the left-hand operand or returns `None` to prune the branch.
+pub trait InvertibleOp<T> {
+    fn apply(&self, lhs: T, rhs: T) -> T;
+
+    fn invert(&self, result: T, rhs: T) -> Option<T>;
+}
+
+/// The usual operators of operator-insertion puzzles. Operands are expected to be positive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Op {
+    Add,
+    Mul,
+    Concat,
+}
+
+impl<T: Integer> InvertibleOp<T> for Op {
+    fn apply(&self, lhs: T, rhs: T) -> T {
+        match self {
+            Op::Add => lhs + rhs,
+            Op::Mul => lhs * rhs,
+            Op::Concat => concat(lhs, rhs),
+        }
+    }
+
+    fn invert(&self, result: T, rhs: T) -> Option<T> {
+        match self {
+            Op::Add => T::from_i128(result.to_i128() - rhs.to_i128()),
+            Op::Mul => (rhs != T::ZERO && result % rhs == T::ZERO).then(|| result / rhs),
+            Op::Concat => strip_suffix(result, rhs),
+        }
+    }
+}
+
+/// Checks whether inserting operators between `operands` (evaluated left to right) can produce `target`.
+///
+/// Searches backwards from the target: each operator is inverted against the last operand,
+/// which prunes most branches immediately (e.g. non-divisible products, missing digit suffixes).
+pub fn solvable<T: Integer, O: InvertibleOp<T>>(operands: &[T], ops: &[O], target: T) -> bool {
+    match operands {
+        [] => false,
+        [first] => *first == target,
+        [rest @ .., last] => ops
+            .iter()
+            .filter_map(|op| op.invert(target, *last))
+            .any(|prev| solvable(rest, ops, prev)),
+    }
+}
+
+/// Like [`solvable`], but returns the operators used, in left-to-right order.
+pub fn solve<T: Integer, O: InvertibleOp<T> + Clone>(
+    operands: &[T],
+    ops: &[O],
+    target: T,
+) -> Option<Vec<O>> {
+    match operands {
+        [] => None,
+        [first] => (*first == target).then(Vec::new),
+        [rest @ .., last] => ops.iter().find_map(|op| {
+            let prev = op.invert(target, *last)?;
+            let mut used = solve(rest, ops, prev)?;
+            used.push(op.clone());
+            Some(used)
+        }),
+    }
+}
+
+/// Evaluates `operands` left to right with the given operators.
+pub fn evaluate<T: Integer, O: InvertibleOp<T>>(operands: &[T], ops: &[O]) -> Option<T> {
+    let (first, rest) = operands.split_first()?;
+    Some(
+        rest.iter()
+            .zip(ops)
+            .fold(*first, |acc, (n, op)| op.apply(acc, *n)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_operator_sequences() {
+        let ops = [Op::Add, Op::Mul];
+        assert!(solvable(&[81u64, 40, 27], &ops, 3267));
+        assert!(!solvable(&[17u64, 5], &ops, 83));
+        assert!(solvable(&[15u64, 6], &[Op::Add, Op::Mul, Op::Concat], 156));
+
+        let ops = [Op::Add, Op::Mul, Op::Concat];
+        let used = solve(&[6u64, 8, 6, 15], &ops, 7290).unwrap();
+        assert_eq!(used, vec![Op::Mul, Op::Concat, Op::Mul]);
+        assert_eq!(evaluate(&[6u64, 8, 6, 15], &used), Some(7290));
+    }
+}
@@ -1,42 +1,36 @@
+use advent_of_code::{collections::Counter, parse::ParseError, scanner::fields};
+
 advent_of_code::solution!(1);
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let mut list1 = vec![];
-    let mut list2 = vec![];
-    for line in input.lines() {
-        let mut bits = line.split("   ");
-        list1.push(bits.next().unwrap().parse::<u32>().unwrap());
-        list2.push(bits.next().unwrap().parse::<u32>().unwrap());
+fn parse(input: &str) -> Result<(Vec<u32>, Vec<u32>), ParseError> {
+    let (mut list1, mut list2) = (Vec::new(), Vec::new());
+    for (i, line) in input.lines().enumerate().filter(|(_, l)| !l.is_empty()) {
+        let [a, b] = fields(line, b" ").array().map_err(|e| e.with_line(i + 1))?;
+        list1.push(a);
+        list2.push(b);
     }
+    Ok((list1, list2))
+}
+
+pub fn part_one(input: &str) -> Result<u32, ParseError> {
+    let (mut list1, mut list2) = parse(input)?;
     list1.sort_unstable();
     list2.sort_unstable();
     let mut sum = 0;
     for (a, b) in list1.iter().zip(list2.iter()) {
         sum += a.abs_diff(*b);
     }
-    Some(sum)
+    Ok(sum)
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let mut list1 = vec![];
-    let mut list2 = vec![];
-    for line in input.lines() {
-        let mut bits = line.split("   ");
-        list1.push(bits.next().unwrap().parse::<u32>().unwrap());
-        list2.push(bits.next().unwrap().parse::<u32>().unwrap());
-    }
-    let mut score = 0;
-    // loop through list 1 and count how many times each number appears in list 2
-    for num in list1.iter() {
-        let mut count = 0;
-        for num2 in list2.iter() {
-            if num == num2 {
-                count += 1;
-            }
-        }
-        score += num * count;
-    }
-    Some(score)
+pub fn part_two(input: &str) -> Result<u64, ParseError> {
+    let (list1, list2) = parse(input)?;
+    // count how many times each number appears in list 2
+    let counts = Counter::from_iter(list2);
+    Ok(list1
+        .iter()
+        .map(|num| u64::from(*num) * counts.get(num))
+        .sum())
 }
 
 #[cfg(test)]
@@ -46,12 +40,12 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(11));
+        assert_eq!(result, Ok(11));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(31));
+        assert_eq!(result, Ok(31));
     }
 }
@@ -1,42 +1,44 @@
-use regex::Regex;
+use advent_of_code::interpreter::Lexer;
 
 advent_of_code::solution!(3);
 
+enum Instr {
+    Mul(u32, u32),
+    Do,
+    Dont,
+}
+
+fn lexer() -> Lexer<Instr> {
+    Lexer::builder()
+        .pattern(r"mul\((\d{1,3}),(\d{1,3})\)", |args| {
+            Some(Instr::Mul(args.get(0)?, args.get(1)?))
+        })
+        .pattern(r"do\(\)", |_| Some(Instr::Do))
+        .pattern(r"don't\(\)", |_| Some(Instr::Dont))
+        .build()
+        .unwrap()
+}
+
 pub fn part_one(input: &str) -> Option<u32> {
-    let re = Regex::new(r"mul\((\d{1,3}),(\d{1,3})\)").unwrap();
     Some(
-        re.captures_iter(input)
-            .map(|c| {
-                let (_, [a, b]) = c.extract();
-                let a = a.parse::<u32>().unwrap();
-                let b = b.parse::<u32>().unwrap();
-                a * b
+        lexer()
+            .tokens(input)
+            .map(|t| match t.instr {
+                Instr::Mul(a, b) => a * b,
+                _ => 0,
             })
             .sum(),
     )
 }
 
 pub fn part_two(input: &str) -> Option<u32> {
-    let re = Regex::new(r"(?:mul\((\d{1,3},\d{1,3})\))|(do\(\))|(don't\(\))").unwrap();
-    let mut mul_enabled = true;
-    Some(
-        re.captures_iter(input)
-            .map(|c| {
-                let (haystack, [c]) = c.extract();
-                if haystack.starts_with("mul") && mul_enabled {
-                    c.split(',').map(|x| x.parse::<u32>().unwrap()).product()
-                } else if haystack == "do()" {
-                    mul_enabled = true;
-                    0
-                } else if haystack == "don't()" {
-                    mul_enabled = false;
-                    0
-                } else {
-                    0
-                }
-            })
-            .sum(),
-    )
+    let (_, sum) = lexer().run(input, (true, 0), |(mul_enabled, sum), t| match t.instr {
+        Instr::Mul(a, b) if *mul_enabled => *sum += a * b,
+        Instr::Mul(..) => {}
+        Instr::Do => *mul_enabled = true,
+        Instr::Dont => *mul_enabled = false,
+    });
+    Some(sum)
 }
 
 #[cfg(test)]
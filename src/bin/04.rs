@@ -1,67 +1,63 @@
-use advent_of_code::{Dir, Matrix, CARDINALS, ORDINALS};
+use advent_of_code::{grid::GridView, parse::ParseError, Dir, CARDINALS, ORDINALS};
 use itertools::Itertools;
 
 advent_of_code::solution!(4);
 
-pub fn part_one(input: &str) -> Option<usize> {
-    let matrix = Matrix::<char>::from(input);
+pub fn part_one(input: &str) -> Result<usize, ParseError> {
+    let matrix = GridView::new(input)?;
     let mut directions = Vec::from(CARDINALS);
     directions.extend(ORDINALS);
     let possible_start_cells = matrix
         .items()
-        .filter(|cell| cell.val == 'X')
+        .filter(|cell| cell.val == b'X')
         .collect::<Vec<_>>();
-    Some(
-        directions
-            .iter()
-            .cartesian_product(possible_start_cells)
-            .filter(|(dir, cell)| {
-                let mut current_cell = *cell;
-                "XMAS".chars().all(|c| {
-                    let mut char_check = false;
-                    if current_cell.val == c {
-                        char_check = true;
-                    }
-                    if let Some(next_cell) = matrix.neighbor(&current_cell, dir) {
-                        if current_cell == next_cell {
-                            char_check = false;
-                        }
-                        current_cell = next_cell;
+    Ok(directions
+        .iter()
+        .cartesian_product(possible_start_cells)
+        .filter(|(dir, cell)| {
+            let mut current_cell = *cell;
+            b"XMAS".iter().all(|c| {
+                let mut char_check = false;
+                if current_cell.val == *c {
+                    char_check = true;
+                }
+                if let Some(next_cell) = matrix.neighbor(&current_cell, dir) {
+                    if current_cell == next_cell {
+                        char_check = false;
                     }
-                    char_check
-                })
+                    current_cell = next_cell;
+                }
+                char_check
             })
-            .count(),
-    )
+        })
+        .count())
 }
 
-pub fn part_two(input: &str) -> Option<usize> {
-    let matrix = Matrix::<char>::from(input);
-    Some(
-        matrix
-            .items()
-            .filter(|cell| {
-                if cell.val != 'A' {
-                    return false;
-                };
-                let mut diag1match = false;
-                let mut diag2match = false;
-                if let Some(nw) = matrix.neighbor(cell, &Dir::NW) {
-                    if let Some(se) = matrix.neighbor(cell, &Dir::SE) {
-                        diag1match =
-                            nw.val == 'M' && se.val == 'S' || nw.val == 'S' && se.val == 'M';
-                    }
+pub fn part_two(input: &str) -> Result<usize, ParseError> {
+    let matrix = GridView::new(input)?;
+    Ok(matrix
+        .items()
+        .filter(|cell| {
+            if cell.val != b'A' {
+                return false;
+            };
+            let mut diag1match = false;
+            let mut diag2match = false;
+            if let Some(nw) = matrix.neighbor(cell, &Dir::NW) {
+                if let Some(se) = matrix.neighbor(cell, &Dir::SE) {
+                    diag1match =
+                        nw.val == b'M' && se.val == b'S' || nw.val == b'S' && se.val == b'M';
                 }
-                if let Some(sw) = matrix.neighbor(cell, &Dir::SW) {
-                    if let Some(ne) = matrix.neighbor(cell, &Dir::NE) {
-                        diag2match =
-                            sw.val == 'M' && ne.val == 'S' || sw.val == 'S' && ne.val == 'M';
-                    }
+            }
+            if let Some(sw) = matrix.neighbor(cell, &Dir::SW) {
+                if let Some(ne) = matrix.neighbor(cell, &Dir::NE) {
+                    diag2match =
+                        sw.val == b'M' && ne.val == b'S' || sw.val == b'S' && ne.val == b'M';
                 }
-                diag1match && diag2match
-            })
-            .count(),
-    )
+            }
+            diag1match && diag2match
+        })
+        .count())
 }
 
 #[cfg(test)]
@@ -71,12 +67,12 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(18));
+        assert_eq!(result, Ok(18));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(9));
+        assert_eq!(result, Ok(9));
     }
 }
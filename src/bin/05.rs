@@ -1,75 +1,72 @@
+use advent_of_code::{
+    parse::{parse_lines_in, sections, ParseError},
+    scanner::fields,
+};
+
 advent_of_code::solution!(5);
 
 #[derive(Debug)]
 struct OrderRule(u32, u32);
 
-fn parse(input: &str) -> (Vec<OrderRule>, Vec<Vec<u32>>) {
-    let (rules_str, updates_str) = input.split_once("\n\n").unwrap();
-    let rules: Vec<OrderRule> = rules_str
-        .lines()
-        .map(|l| {
-            let (before, after) = l.split_once('|').unwrap();
-            OrderRule(before.parse().unwrap(), after.parse().unwrap())
-        })
-        .collect();
-    let updates: Vec<Vec<u32>> = updates_str
-        .lines()
-        .map(|l| l.split(',').map(|c| c.parse().unwrap()).collect())
-        .collect();
-    (rules, updates)
+fn parse(input: &str) -> Result<(Vec<OrderRule>, Vec<Vec<u32>>), ParseError> {
+    let [rules_str, updates_str] = sections(input)[..] else {
+        return Err(ParseError::new(input, input, "two sections"));
+    };
+    let rules = parse_lines_in(input, rules_str, |l| {
+        let [before, after] = fields(l, b"|").array()?;
+        Ok(OrderRule(before, after))
+    })?;
+    let updates = parse_lines_in(input, updates_str, |l| fields(l, b",").collect())?;
+    Ok((rules, updates))
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let (rules, updates) = parse(input);
-    Some(
-        updates
-            .iter()
-            .filter(|u| {
-                let mut last = u[0];
-                for &n in u.iter().skip(1) {
-                    if !rules.iter().any(|r| r.0 == last && r.1 == n) {
-                        return false;
-                    }
-                    last = n;
+pub fn part_one(input: &str) -> Result<u32, ParseError> {
+    let (rules, updates) = parse(input)?;
+    Ok(updates
+        .iter()
+        .filter(|u| {
+            let mut last = u[0];
+            for &n in u.iter().skip(1) {
+                if !rules.iter().any(|r| r.0 == last && r.1 == n) {
+                    return false;
                 }
-                true
-            })
-            .map(|u| u[u.len() / 2])
-            .sum(),
-    )
+                last = n;
+            }
+            true
+        })
+        .map(|u| u[u.len() / 2])
+        .sum())
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let (rules, updates) = parse(input);
-    Some(
-        updates
-            .iter()
-            .filter(|u| {
-                let mut last = u[0];
-                for &n in u.iter().skip(1) {
-                    if !rules.iter().any(|r| r.0 == last && r.1 == n) {
-                        return true;
-                    }
-                    last = n;
+pub fn part_two(input: &str) -> Result<u32, ParseError> {
+    let (rules, updates) = parse(input)?;
+    Ok(updates
+        .iter()
+        .filter(|u| {
+            let mut last = u[0];
+            for &n in u.iter().skip(1) {
+                if !rules.iter().any(|r| r.0 == last && r.1 == n) {
+                    return true;
                 }
-                false
-            })
-            .map(|u| {
-                let mut u = u.clone();
-                u.sort_unstable_by(|a, b| {
-                    if rules.iter().any(|r| r.0 == *a && r.1 == *b) {
-                        std::cmp::Ordering::Less
-                    } else if rules.iter().any(|r| r.0 == *a && r.1 == *b) {
-                        return std::cmp::Ordering::Greater;
-                    } else {
-                        std::cmp::Ordering::Equal
-                    }
-                });
-                u
-            })
-            .map(|u| u[u.len() / 2])
-            .sum(),
-    )
+                last = n;
+            }
+            false
+        })
+        .map(|u| {
+            let mut u = u.clone();
+            u.sort_unstable_by(|a, b| {
+                if rules.iter().any(|r| r.0 == *a && r.1 == *b) {
+                    std::cmp::Ordering::Less
+                } else if rules.iter().any(|r| r.0 == *a && r.1 == *b) {
+                    std::cmp::Ordering::Greater
+                } else {
+                    std::cmp::Ordering::Equal
+                }
+            });
+            u
+        })
+        .map(|u| u[u.len() / 2])
+        .sum())
 }
 
 #[cfg(test)]
@@ -79,12 +76,24 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(143));
+        assert_eq!(result, Ok(143));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(123));
+        assert_eq!(result, Ok(123));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let err = part_one("47|53\n97|x1\n\n75,47\n").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (Some(2), 4, "x"));
+    }
+
+    #[test]
+    fn test_update_parse_error() {
+        let err = part_one("\n47|53\n97|47\n\n75,47\n75,4x7\n").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (Some(6), 5, "x"));
     }
 }
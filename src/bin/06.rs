@@ -1,6 +1,6 @@
-use std::collections::HashSet;
-
-use advent_of_code::{Dir, Matrix, Point};
+use advent_of_code::{
+    hashing::FastHashSet, parallel::par_filter_count, parse::ParseError, Dir, Matrix, Point,
+};
 
 advent_of_code::solution!(6);
 
@@ -14,9 +14,9 @@ fn turn_right(dir: &Dir) -> Dir {
     }
 }
 
-pub fn part_one(input: &str) -> Option<u32> {
-    let matrix = Matrix::<char>::from(input);
-    let mut visited = HashSet::<Point>::new();
+pub fn part_one(input: &str) -> Result<u32, ParseError> {
+    let matrix = Matrix::<char>::try_from(input)?;
+    let mut visited = FastHashSet::<Point>::default();
     let mut current_dir = Dir::N;
     let mut current_cell = matrix.items().find(|c| c.val == '^').unwrap();
     visited.insert(current_cell.point);
@@ -31,22 +31,21 @@ pub fn part_one(input: &str) -> Option<u32> {
             }
         }
     }
-    Some(visited.len() as u32)
+    Ok(visited.len() as u32)
 }
 
-pub fn part_two(input: &str) -> Option<u32> {
-    let matrix = Matrix::<char>::from(input);
+pub fn part_two(input: &str) -> Result<u32, ParseError> {
+    let matrix = Matrix::<char>::try_from(input)?;
     let possible_changes = matrix.items().filter(|c| c.val == '.').collect::<Vec<_>>();
-    let mut loops = 0;
-    for change in possible_changes {
-        let mut visited = HashSet::<(Point, Dir)>::new();
+    let start = matrix.items().find(|c| c.val == '^').unwrap();
+    let loops = par_filter_count(&possible_changes, |change| {
+        let mut visited = FastHashSet::<(Point, Dir)>::default();
         let mut current_dir = Dir::N;
-        let mut current_cell = matrix.items().find(|c| c.val == '^').unwrap();
+        let mut current_cell = start;
         visited.insert((current_cell.point, current_dir));
         while let Some(cell) = matrix.neighbor(&current_cell, &current_dir) {
             if visited.contains(&(cell.point, current_dir)) {
-                loops += 1;
-                break;
+                return true;
             }
             match cell.val {
                 '#' => {
@@ -61,8 +60,9 @@ pub fn part_two(input: &str) -> Option<u32> {
                 }
             }
         }
-    }
-    Some(loops)
+        false
+    });
+    Ok(loops as u32)
 }
 
 #[cfg(test)]
@@ -72,12 +72,12 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(41));
+        assert_eq!(result, Ok(41));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(6));
+        assert_eq!(result, Ok(6));
     }
 }
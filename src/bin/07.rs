@@ -1,75 +1,42 @@
+use advent_of_code::{
+    backtrack::{solvable, Op},
+    parse::ParseError,
+    scanner::fields,
+};
+
 advent_of_code::solution!(7);
 
-fn parse(input: &str) -> Vec<(u64, Vec<u64>)> {
-    input
-        .lines()
-        .map(|l| {
-            let mut parts = l.split(':');
-            let result = parts.next().unwrap().trim().parse().unwrap();
-            let nums = parts
-                .next()
-                .unwrap()
-                .split_whitespace()
-                .map(|n| n.parse().unwrap())
-                .collect();
-            (result, nums)
-        })
-        .collect()
+fn calibration_result(input: &str, ops: &[Op]) -> Result<u64, ParseError> {
+    let mut numbers = Vec::new();
+    let mut sum = 0;
+    for (i, line) in input.lines().enumerate().filter(|(_, l)| !l.is_empty()) {
+        let at_line = |e: ParseError| e.with_line(i + 1);
+        let mut scan = fields::<u64>(line, b": ");
+        let result = match scan.next() {
+            Some(result) => result.map_err(at_line)?,
+            None => return Err(at_line(ParseError::new(line, line, "a test value"))),
+        };
+        let rest = &line[scan.position()..];
+        if !rest.starts_with(':') {
+            return Err(at_line(ParseError::new(line, rest, "`:`")));
+        }
+        numbers.clear();
+        for n in scan {
+            numbers.push(n.map_err(at_line)?);
+        }
+        if solvable(&numbers, ops, result) {
+            sum += result;
+        }
+    }
+    Ok(sum)
 }
 
-pub fn part_one(input: &str) -> Option<u64> {
-    let data = parse(input);
-    data.iter()
-        .filter(|(result, numbers)| {
-            numbers
-                .iter()
-                .fold(vec![], |mut acc, n| {
-                    if acc.is_empty() {
-                        acc.push(*n);
-                    } else {
-                        let mut new_acc = vec![];
-                        for a in acc {
-                            new_acc.push(a + n);
-                            new_acc.push(a * n);
-                        }
-                        acc = new_acc;
-                    }
-                    acc
-                })
-                .iter()
-                .any(|n| n == result)
-        })
-        .map(|(result, _)| *result)
-        .sum::<u64>()
-        .into()
+pub fn part_one(input: &str) -> Result<u64, ParseError> {
+    calibration_result(input, &[Op::Add, Op::Mul])
 }
 
-pub fn part_two(input: &str) -> Option<u64> {
-    let data = parse(input);
-    data.iter()
-        .filter(|(result, numbers)| {
-            numbers
-                .iter()
-                .fold(vec![], |mut acc, n| {
-                    if acc.is_empty() {
-                        acc.push(*n);
-                    } else {
-                        let mut new_acc = vec![];
-                        for a in acc {
-                            new_acc.push(a + n);
-                            new_acc.push(a * n);
-                            new_acc.push((a.to_string() + &n.to_string()).parse().unwrap());
-                        }
-                        acc = new_acc;
-                    }
-                    acc
-                })
-                .iter()
-                .any(|n| n == result)
-        })
-        .map(|(result, _)| *result)
-        .sum::<u64>()
-        .into()
+pub fn part_two(input: &str) -> Result<u64, ParseError> {
+    calibration_result(input, &[Op::Add, Op::Mul, Op::Concat])
 }
 
 #[cfg(test)]
@@ -79,12 +46,12 @@ mod tests {
     #[test]
     fn test_part_one() {
         let result = part_one(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(3749));
+        assert_eq!(result, Ok(3749));
     }
 
     #[test]
     fn test_part_two() {
         let result = part_two(&advent_of_code::template::read_file("examples", DAY));
-        assert_eq!(result, Some(11387));
+        assert_eq!(result, Ok(11387));
     }
 }
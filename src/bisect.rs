@@ -0,0 +1,187 @@
+use std::{
+    convert::Infallible,
+    ops::Range,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use crate::math::Integer;
+
+// Predicate evaluations across all searches, reported by the runner in debug builds.
+static EVALUATIONS: AtomicU64 = AtomicU64::new(0);
+
+fn count_evaluation() {
+    #[cfg(debug_assertions)]
+    EVALUATIONS.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Smallest value in `range` for which `pred` holds, `None` if it holds for none.
+/// `pred` has to be monotone: once true, it stays true for every larger value.
+pub fn bisect<T: Integer>(range: Range<T>, mut pred: impl FnMut(T) -> bool) -> Option<T> {
+    let Ok(found) = try_bisect(range, |n| Ok::<_, Infallible>(pred(n)));
+    found
+}
+
+/// Like [`bisect`], stopping at the first error `pred` returns.
+pub fn try_bisect<T: Integer, E>(
+    range: Range<T>,
+    mut pred: impl FnMut(T) -> Result<bool, E>,
+) -> Result<Option<T>, E> {
+    let (mut lo, mut hi) = (range.start, range.end);
+    while lo < hi {
+        let mid = midpoint(lo, hi);
+        count_evaluation();
+        if pred(mid)? {
+            hi = mid;
+        } else {
+            lo = mid + T::ONE;
+        }
+    }
+    Ok((lo < range.end).then_some(lo))
+}
+
+/// A value in `lo..hi`, halfway between the two, without computing `hi - lo` (which overflows
+/// for ranges spanning more than half of a signed type).
+fn midpoint<T: Integer>(lo: T, hi: T) -> T {
+    let two = T::ONE + T::ONE;
+    let mid = lo / two + hi / two + (lo % two + hi % two) / two;
+    // truncating division can round up to `hi` when the two are adjacent
+    if mid == hi {
+        lo
+    } else {
+        mid
+    }
+}
+
+/// Index of the first item of a slice for which `pred` holds, `None` if it holds for none.
+pub fn bisect_slice<T>(items: &[T], mut pred: impl FnMut(&T) -> bool) -> Option<usize> {
+    bisect(0..items.len(), |i| pred(&items[i]))
+}
+
+/// Like [`bisect_slice`], stopping at the first error `pred` returns.
+pub fn try_bisect_slice<T, E>(
+    items: &[T],
+    mut pred: impl FnMut(&T) -> Result<bool, E>,
+) -> Result<Option<usize>, E> {
+    try_bisect(0..items.len(), |i| pred(&items[i]))
+}
+
+/// Smallest value from `start` upwards for which `pred` holds, when there is no known upper bound.
+/// Probes `start, start + 1, start + 2, start + 4, ...` until `pred` holds, then bisects the last gap.
+/// A probe that would overflow is clamped to `T::MAX`, so `None` means `pred` doesn't even hold there.
+pub fn exponential_search<T: Integer>(start: T, mut pred: impl FnMut(T) -> bool) -> Option<T> {
+    let Ok(found) = try_exponential_search(start, |n| Ok::<_, Infallible>(pred(n)));
+    found
+}
+
+/// Like [`exponential_search`], stopping at the first error `pred` returns.
+pub fn try_exponential_search<T: Integer, E>(
+    start: T,
+    mut pred: impl FnMut(T) -> Result<bool, E>,
+) -> Result<Option<T>, E> {
+    let mut lo = start;
+    let mut probe = start;
+    let mut step = T::ONE;
+    loop {
+        count_evaluation();
+        if pred(probe)? {
+            break;
+        }
+        if probe == T::MAX {
+            return Ok(None);
+        }
+        lo = probe + T::ONE;
+        // once the step stops growing the probe would repeat, so go straight to the end instead
+        probe = start
+            .checked_add(step)
+            .filter(|&next| next > probe)
+            .unwrap_or(T::MAX);
+        step = step.checked_add(step).unwrap_or(T::MAX);
+    }
+    // `probe` is known to match, so only the values before it need searching.
+    Ok(Some(try_bisect(lo..probe, pred)?.unwrap_or(probe)))
+}
+
+/// Predicate evaluations summed over every search since the last [`reset_evaluations`].
+/// Only tracked in debug builds, always `0` in release.
+pub fn evaluations() -> u64 {
+    EVALUATIONS.load(Ordering::Relaxed)
+}
+
+pub fn reset_evaluations() {
+    EVALUATIONS.store(0, Ordering::Relaxed);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::VecDeque;
+
+    use super::*;
+    use crate::hashing::FastHashSet;
+
+    #[test]
+    fn finds_first_match() {
+        assert_eq!(bisect(0..100, |n| n * n >= 50), Some(8));
+        assert_eq!(bisect(0..5, |n| n > 10), None);
+        assert_eq!(bisect(-20i64..20, |n| n >= -3), Some(-3));
+        assert_eq!(bisect_slice(&[1, 3, 5, 7, 9], |n| *n > 4), Some(2));
+
+        let result = try_bisect_slice(&["1", "2", "x", "4"], |s| s.parse::<u32>().map(|n| n > 3));
+        assert!(result.is_err());
+        assert_eq!(try_bisect(0..10, |n| Ok::<_, ()>(n >= 4)), Ok(Some(4)));
+
+        for target in [i64::MIN, -7, 0, 12345, i64::MAX - 1] {
+            assert_eq!(bisect(i64::MIN..i64::MAX, |n| n >= target), Some(target));
+        }
+        assert_eq!(bisect(i64::MIN..i64::MAX, |_| false), None);
+        assert_eq!(bisect(i128::MIN..i128::MAX, |n| n >= -1), Some(-1));
+    }
+
+    #[test]
+    fn searches_without_upper_bound() {
+        assert_eq!(exponential_search(0u64, |n| n * n >= 1000), Some(32));
+        assert_eq!(exponential_search(5u64, |n| n >= 5), Some(5));
+        assert_eq!(exponential_search(200u8, |n| n >= 250), Some(250));
+        assert_eq!(
+            exponential_search(0u32, |n| n >= 3_000_000_000),
+            Some(3_000_000_000)
+        );
+        assert_eq!(exponential_search(-100i8, |n| n >= 120), Some(120));
+        assert_eq!(exponential_search(0u8, |_| false), None);
+        assert_eq!(
+            exponential_search(100i64, |n| n >= 1_000_003),
+            Some(1_000_003)
+        );
+    }
+
+    #[test]
+    fn finds_first_blocking_byte() {
+        // memory space from 2024 day 18
+        let input = "5,4 4,2 4,5 3,0 2,1 6,3 2,4 1,5 0,6 3,3 2,6 5,1 1,2 \
+                     5,5 2,5 6,5 1,4 0,4 6,4 1,1 6,1 1,0 0,5 1,6 2,0";
+        let bytes = input
+            .split(' ')
+            .map(|b| {
+                let (x, y) = b.split_once(',').unwrap();
+                (x.parse::<i32>().unwrap(), y.parse::<i32>().unwrap())
+            })
+            .collect::<Vec<_>>();
+
+        let blocked = |n: usize| {
+            let walls = bytes[..n].iter().collect::<FastHashSet<_>>();
+            let mut seen = FastHashSet::from_iter([(0, 0)]);
+            let mut queue = VecDeque::from([(0, 0)]);
+            while let Some((x, y)) = queue.pop_front() {
+                for next in [(x + 1, y), (x - 1, y), (x, y + 1), (x, y - 1)] {
+                    let in_bounds = (0..7).contains(&next.0) && (0..7).contains(&next.1);
+                    if in_bounds && !walls.contains(&next) && seen.insert(next) {
+                        queue.push_back(next);
+                    }
+                }
+            }
+            !seen.contains(&(6, 6))
+        };
+
+        let n = bisect(0..bytes.len() + 1, blocked).unwrap();
+        assert_eq!(bytes[n - 1], (6, 1));
+    }
+}
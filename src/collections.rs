@@ -0,0 +1,269 @@
+use std::{
+    cmp::Reverse,
+    collections::hash_map,
+    hash::Hash,
+    ops::{Add, Index, IndexMut, Sub},
+};
+
+use crate::hashing::FastHashMap;
+
+/// A multiset: counts how often each distinct item occurs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Counter<T: Hash + Eq> {
+    counts: FastHashMap<T, u64>,
+}
+
+impl<T: Hash + Eq> Counter<T> {
+    pub fn new() -> Self {
+        Self {
+            counts: FastHashMap::default(),
+        }
+    }
+
+    pub fn insert(&mut self, item: T) {
+        self.insert_n(item, 1);
+    }
+
+    pub fn insert_n(&mut self, item: T, n: u64) {
+        if n > 0 {
+            *self.counts.entry(item).or_default() += n;
+        }
+    }
+
+    /// Removes up to `n` occurrences of `item`, dropping it once its count reaches zero.
+    pub fn remove_n(&mut self, item: &T, n: u64) {
+        if let Some(count) = self.counts.get_mut(item) {
+            *count = count.saturating_sub(n);
+            if *count == 0 {
+                self.counts.remove(item);
+            }
+        }
+    }
+
+    /// Occurrences of `item`, `0` if it was never added.
+    pub fn get(&self, item: &T) -> u64 {
+        self.counts.get(item).copied().unwrap_or(0)
+    }
+
+    /// Number of distinct items.
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Sum of all counts.
+    pub fn total(&self) -> u64 {
+        self.counts.values().sum()
+    }
+
+    pub fn iter(&self) -> hash_map::Iter<'_, T, u64> {
+        self.counts.iter()
+    }
+
+    /// Items ordered by descending count. The order of items with equal counts is unspecified.
+    pub fn most_common(&self) -> Vec<(&T, u64)> {
+        let mut items = self.counts.iter().map(|(k, v)| (k, *v)).collect::<Vec<_>>();
+        items.sort_unstable_by_key(|&(_, count)| Reverse(count));
+        items
+    }
+
+    /// Keeps the smaller count of every item present in both counters.
+    pub fn intersection(&self, other: &Self) -> Self
+    where
+        T: Clone,
+    {
+        let mut result = Counter::new();
+        for (k, v) in &self.counts {
+            result.insert_n(k.clone(), (*v).min(other.get(k)));
+        }
+        result
+    }
+
+    /// Keeps the larger count of every item present in either counter.
+    pub fn union(&self, other: &Self) -> Self
+    where
+        T: Clone,
+    {
+        let mut result = self.clone();
+        for (k, v) in &other.counts {
+            let count = result.counts.entry(k.clone()).or_default();
+            *count = (*count).max(*v);
+        }
+        result
+    }
+
+    /// Replaces every distinct item by the items `transform` produces for it, weighted by its count,
+    /// for `rounds` rounds. Keeps puzzles where items multiply each round tractable, as equal items
+    /// are only transformed once per round.
+    pub fn evolve<I: IntoIterator<Item = T>>(
+        &self,
+        rounds: usize,
+        transform: impl Fn(&T) -> I,
+    ) -> Self
+    where
+        T: Clone,
+    {
+        let mut current = self.clone();
+        for _ in 0..rounds {
+            let mut next = Counter::new();
+            for (item, count) in &current.counts {
+                for produced in transform(item) {
+                    next.insert_n(produced, *count);
+                }
+            }
+            current = next;
+        }
+        current
+    }
+}
+
+impl<T: Hash + Eq> Default for Counter<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Hash + Eq> FromIterator<T> for Counter<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut counter = Self::new();
+        for item in iter {
+            counter.insert(item);
+        }
+        counter
+    }
+}
+
+impl<T: Hash + Eq> Add for Counter<T> {
+    type Output = Self;
+
+    fn add(mut self, rhs: Self) -> Self {
+        for (k, v) in rhs.counts {
+            self.insert_n(k, v);
+        }
+        self
+    }
+}
+
+/// Subtracts counts, dropping items whose count reaches zero.
+impl<T: Hash + Eq> Sub for Counter<T> {
+    type Output = Self;
+
+    fn sub(mut self, rhs: Self) -> Self {
+        for (k, v) in rhs.counts {
+            self.remove_n(&k, v);
+        }
+        self
+    }
+}
+
+/// A map that hands out a default value for missing keys.
+/// Reading never inserts, writing through `get_mut` or `map[&key]` inserts the default first.
+#[derive(Debug, Clone)]
+pub struct DefaultMap<K: Hash + Eq, V: Clone> {
+    map: FastHashMap<K, V>,
+    default: V,
+}
+
+impl<K: Hash + Eq, V: Clone> DefaultMap<K, V> {
+    pub fn new(default: V) -> Self {
+        Self {
+            map: FastHashMap::default(),
+            default,
+        }
+    }
+
+    pub fn get(&self, key: &K) -> &V {
+        self.map.get(key).unwrap_or(&self.default)
+    }
+
+    pub fn get_mut(&mut self, key: K) -> &mut V {
+        self.map.entry(key).or_insert_with(|| self.default.clone())
+    }
+
+    pub fn insert(&mut self, key: K, val: V) -> Option<V> {
+        self.map.insert(key, val)
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.map.contains_key(key)
+    }
+
+    /// Number of explicitly stored keys.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn iter(&self) -> hash_map::Iter<'_, K, V> {
+        self.map.iter()
+    }
+}
+
+impl<K: Hash + Eq, V: Clone> Index<&K> for DefaultMap<K, V> {
+    type Output = V;
+
+    fn index(&self, key: &K) -> &V {
+        self.get(key)
+    }
+}
+
+impl<K: Hash + Eq + Clone, V: Clone> IndexMut<&K> for DefaultMap<K, V> {
+    fn index_mut(&mut self, key: &K) -> &mut V {
+        self.get_mut(key.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_items() {
+        let counter = Counter::from_iter("abracadabra".chars());
+        assert_eq!(counter.get(&'a'), 5);
+        assert_eq!(counter.get(&'z'), 0);
+        assert_eq!(counter.total(), 11);
+        assert_eq!(counter.most_common()[0], (&'a', 5));
+
+        let other = Counter::from_iter("aab".chars());
+        assert_eq!((counter.clone() - other.clone()).get(&'a'), 3);
+        assert_eq!((counter.clone() + other.clone()).get(&'b'), 3);
+        assert_eq!(counter.intersection(&other).total(), 3);
+        assert_eq!(counter.union(&other).total(), 11);
+    }
+
+    #[test]
+    fn evolves_multisets() {
+        // stones from 2024 day 11
+        let stones = Counter::from_iter([125u64, 17]);
+        let blink = |n: &u64| -> Vec<u64> {
+            let digits = n.to_string();
+            match *n {
+                0 => vec![1],
+                _ if digits.len().is_multiple_of(2) => {
+                    let (a, b) = digits.split_at(digits.len() / 2);
+                    vec![a.parse().unwrap(), b.parse().unwrap()]
+                }
+                n => vec![n * 2024],
+            }
+        };
+        assert_eq!(stones.evolve(6, blink).total(), 22);
+        assert_eq!(stones.evolve(25, blink).total(), 55312);
+    }
+
+    #[test]
+    fn defaults_missing_keys() {
+        let mut map = DefaultMap::new(vec![]);
+        assert!(map[&"a"].is_empty());
+        map[&"a"].push(1);
+        map.get_mut("a").push(2);
+        assert_eq!(map[&"a"], vec![1, 2]);
+        assert_eq!(map.len(), 1);
+    }
+}
@@ -0,0 +1,236 @@
+use std::collections::VecDeque;
+
+use crate::{Matrix, Point, CARDINALS};
+
+/// Sorted distinct breakpoints of one axis. Every original coordinate `v` gets the breakpoints
+/// `v` and `v + 1`, so it keeps a compressed column (or row) of its own that is one unit wide,
+/// and the gaps between them collapse into single wide cells. One cell of padding is added on
+/// both ends so the outside of any shape drawn on the grid is connected.
+fn breakpoints(values: impl Iterator<Item = i64>) -> Vec<i64> {
+    let mut breaks = values.flat_map(|v| [v, v + 1]).collect::<Vec<_>>();
+    breaks.sort_unstable();
+    breaks.dedup();
+    if let (Some(&min), Some(&max)) = (breaks.first(), breaks.last()) {
+        breaks.insert(0, min - 1);
+        breaks.push(max + 1);
+    }
+    breaks
+}
+
+/// Index of the cell of `breaks` holding `v`, i.e. the `i` with `breaks[i] <= v < breaks[i + 1]`.
+fn cell_of(breaks: &[i64], v: i64) -> Option<usize> {
+    let i = breaks.partition_point(|&b| b <= v).checked_sub(1)?;
+    (i + 1 < breaks.len()).then_some(i)
+}
+
+/// Maps huge, sparse coordinates onto a small grid. Each compressed cell stands for a rectangle
+/// of original cells, whose size is its weight, so areas can be counted on the small grid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompressedGrid {
+    xs: Vec<i64>,
+    ys: Vec<i64>,
+}
+
+impl CompressedGrid {
+    pub fn new<'a>(points: impl IntoIterator<Item = &'a Point<i64>>) -> Self {
+        let points = points.into_iter().collect::<Vec<_>>();
+        Self {
+            xs: breakpoints(points.iter().map(|p| p.x)),
+            ys: breakpoints(points.iter().map(|p| p.y)),
+        }
+    }
+
+    /// Number of compressed columns.
+    pub fn width(&self) -> usize {
+        self.xs.len().saturating_sub(1)
+    }
+
+    /// Number of compressed rows.
+    pub fn height(&self) -> usize {
+        self.ys.len().saturating_sub(1)
+    }
+
+    /// The compressed cell holding `point`, `None` outside the padded bounds.
+    pub fn compress(&self, point: &Point<i64>) -> Option<Point> {
+        Some(Point {
+            x: cell_of(&self.xs, point.x)?,
+            y: cell_of(&self.ys, point.y)?,
+        })
+    }
+
+    /// Top-left original coordinate of a compressed cell.
+    pub fn decompress(&self, point: &Point) -> Point<i64> {
+        Point {
+            x: self.xs[point.x],
+            y: self.ys[point.y],
+        }
+    }
+
+    /// Number of original cells a compressed cell stands for.
+    pub fn weight(&self, point: &Point) -> i128 {
+        let width = self.xs[point.x + 1] as i128 - self.xs[point.x] as i128;
+        let height = self.ys[point.y + 1] as i128 - self.ys[point.y] as i128;
+        width * height
+    }
+
+    /// A matrix over the compressed space with every cell set to `val`.
+    pub fn matrix<T: Clone>(&self, val: T) -> Matrix<T> {
+        Matrix::from_fn(self.width(), self.height(), |_| val.clone())
+    }
+
+    /// Sets every cell on the axis-aligned lines between consecutive `vertices` to `val`,
+    /// closing the loop back to the first vertex.
+    pub fn trace<T: Copy>(&self, matrix: &mut Matrix<T>, vertices: &[Point<i64>], val: T) {
+        for (a, b) in vertices.iter().zip(vertices.iter().cycle().skip(1)) {
+            assert!(
+                a.x == b.x || a.y == b.y,
+                "{a:?} and {b:?} are not on an axis-aligned line"
+            );
+            let (a, b) = (self.compress(a).unwrap(), self.compress(b).unwrap());
+            for y in a.y.min(b.y)..=a.y.max(b.y) {
+                for x in a.x.min(b.x)..=a.x.max(b.x) {
+                    matrix.cells[y][x] = val;
+                }
+            }
+        }
+    }
+
+    /// Total weight of the cells of `matrix` for which `predicate` holds.
+    pub fn area<T>(&self, matrix: &Matrix<T>, predicate: impl Fn(&T) -> bool) -> i128 {
+        matrix
+            .items_ref()
+            .filter(|c| predicate(c.val))
+            .map(|c| self.weight(&c.point))
+            .sum()
+    }
+
+    /// Number of original lattice cells inside or on the outline of the polygon with the
+    /// given axis-aligned edges, however far apart its vertices are.
+    pub fn enclosed_area(vertices: &[Point<i64>]) -> i128 {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        enum Tile {
+            Inside,
+            Edge,
+            Outside,
+        }
+
+        let grid = Self::new(vertices);
+        let mut matrix = grid.matrix(Tile::Inside);
+        grid.trace(&mut matrix, vertices, Tile::Edge);
+        // the padding guarantees the corner is outside
+        flood_fill(&mut matrix, Point { x: 0, y: 0 }, Tile::Outside);
+        grid.area(&matrix, |t| *t != Tile::Outside)
+    }
+}
+
+/// Replaces the region of equal, cardinally connected cells around `start` with `val`.
+/// Returns the number of cells changed.
+pub fn flood_fill<T: Copy + PartialEq>(matrix: &mut Matrix<T>, start: Point, val: T) -> usize {
+    let Some(target) = matrix.get(start.x, start.y) else {
+        return 0;
+    };
+    if target == val {
+        return 0;
+    }
+
+    let mut filled = 0;
+    matrix.cells[start.y][start.x] = val;
+    let mut queue = VecDeque::from([start]);
+    while let Some(point) = queue.pop_front() {
+        filled += 1;
+        for dir in &CARDINALS {
+            if let Some(next) = matrix.neighbor_at(&point, dir.offset()) {
+                if next.val == target {
+                    matrix.cells[next.point.y][next.point.x] = val;
+                    queue.push_back(next.point);
+                }
+            }
+        }
+    }
+    filled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{geometry::Polygon, Dir};
+
+    fn p(x: i64, y: i64) -> Point<i64> {
+        Point { x, y }
+    }
+
+    #[test]
+    fn maps_points_both_ways() {
+        let grid = CompressedGrid::new(&[p(0, 0), p(1_000_000, 5), p(7, -3)]);
+        assert_eq!((grid.width(), grid.height()), (7, 7));
+
+        let far = grid.compress(&p(1_000_000, 5)).unwrap();
+        assert_eq!(grid.decompress(&far), p(1_000_000, 5));
+        assert_eq!(grid.weight(&far), 1);
+
+        // the gap between 8 and 1_000_000 collapses into one cell
+        let gap = grid.compress(&p(500, 0)).unwrap();
+        assert_eq!(grid.decompress(&gap), p(8, 0));
+        assert_eq!(grid.weight(&gap), 1_000_000 - 8);
+        assert_eq!(grid.compress(&p(2_000_000, 0)), None);
+    }
+
+    #[test]
+    fn fills_huge_polygons() {
+        let rectangle = [
+            p(0, 0),
+            p(999_999, 0),
+            p(999_999, 1_999_999),
+            p(0, 1_999_999),
+        ];
+        assert_eq!(
+            CompressedGrid::enclosed_area(&rectangle),
+            1_000_000 * 2_000_000
+        );
+
+        // corner products past the i64 range
+        let big = 3_000_000_000;
+        let square = [p(-big, -big), p(big, -big), p(big, big), p(-big, big)];
+        assert_eq!(
+            CompressedGrid::enclosed_area(&square),
+            (2 * big as i128 + 1).pow(2)
+        );
+
+        // lagoon from 2023 day 18, scaled up
+        let plan = [
+            (Dir::E, 6),
+            (Dir::S, 5),
+            (Dir::W, 2),
+            (Dir::S, 2),
+            (Dir::E, 2),
+            (Dir::S, 2),
+            (Dir::W, 5),
+            (Dir::N, 2),
+            (Dir::W, 1),
+            (Dir::N, 2),
+            (Dir::E, 2),
+            (Dir::N, 3),
+            (Dir::W, 2),
+            (Dir::N, 2),
+        ];
+        for scale in [1, 1000, 123_457] {
+            let lagoon = Polygon::from_steps(p(0, 0), plan.map(|(d, len)| (d, len * scale)));
+            assert_eq!(
+                CompressedGrid::enclosed_area(&lagoon.vertices),
+                lagoon.lattice_points()
+            );
+        }
+        assert_eq!(
+            CompressedGrid::enclosed_area(&Polygon::from_steps(p(0, 0), plan).vertices),
+            62
+        );
+    }
+
+    #[test]
+    fn floods_regions() {
+        let mut matrix = Matrix::<char>::try_from("..#\n.#.\n#..").unwrap();
+        assert_eq!(flood_fill(&mut matrix, Point { x: 0, y: 0 }, 'o'), 3);
+        assert_eq!(matrix.to_string(), "oo#\no#.\n#..\n");
+        assert_eq!(flood_fill(&mut matrix, Point { x: 0, y: 0 }, 'o'), 0);
+    }
+}
@@ -0,0 +1,275 @@
+use crate::{hashing::FastHashMap, Dir, Matrix, Point};
+
+pub type EntityId = usize;
+
+/// Something that moves over the terrain. It covers `width` cells to the right of `origin`,
+/// so wide boxes (`[]`) are a single entity.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entity<K> {
+    pub kind: K,
+    pub origin: Point,
+    pub width: usize,
+}
+
+impl<K> Entity<K> {
+    pub fn points(&self) -> impl Iterator<Item = Point> + '_ {
+        (0..self.width).map(|dx| Point {
+            x: self.origin.x + dx,
+            y: self.origin.y,
+        })
+    }
+}
+
+/// Moving entities on top of a borrowed, never modified terrain.
+/// Cloning only copies the entities, so it is cheap to snapshot a state and try moves on the copy.
+#[derive(Debug, Clone)]
+pub struct EntityLayer<'a, T, K> {
+    terrain: &'a Matrix<T>,
+    is_wall: fn(&T) -> bool,
+    entities: Vec<Entity<K>>,
+    occupied: FastHashMap<Point, EntityId>,
+}
+
+impl<'a, T, K> EntityLayer<'a, T, K> {
+    pub fn new(terrain: &'a Matrix<T>, is_wall: fn(&T) -> bool) -> Self {
+        Self {
+            terrain,
+            is_wall,
+            entities: vec![],
+            occupied: FastHashMap::default(),
+        }
+    }
+
+    pub fn terrain(&self) -> &'a Matrix<T> {
+        self.terrain
+    }
+
+    /// Places a new entity. `None` if any of its cells is a wall, outside the terrain or taken.
+    pub fn spawn(&mut self, kind: K, origin: Point, width: usize) -> Option<EntityId> {
+        let entity = Entity {
+            kind,
+            origin,
+            width,
+        };
+        if !entity.points().all(|p| self.is_free(&p)) {
+            return None;
+        }
+
+        let id = self.entities.len();
+        for p in entity.points() {
+            self.occupied.insert(p, id);
+        }
+        self.entities.push(entity);
+        Some(id)
+    }
+
+    pub fn get(&self, id: EntityId) -> &Entity<K> {
+        &self.entities[id]
+    }
+
+    pub fn entities(&self) -> impl Iterator<Item = (EntityId, &Entity<K>)> {
+        self.entities.iter().enumerate()
+    }
+
+    /// The entity covering `point`, if any.
+    pub fn at(&self, point: &Point) -> Option<EntityId> {
+        self.occupied.get(point).copied()
+    }
+
+    /// Whether `point` is outside the terrain or a wall.
+    pub fn is_wall(&self, point: &Point) -> bool {
+        self.terrain
+            .get_ref(point.x, point.y)
+            .is_none_or(|t| (self.is_wall)(t))
+    }
+
+    /// Whether `point` is neither a wall nor covered by an entity.
+    pub fn is_free(&self, point: &Point) -> bool {
+        !self.is_wall(point) && !self.occupied.contains_key(point)
+    }
+
+    fn target(&self, point: &Point, dir: &Dir) -> Option<Point> {
+        self.terrain
+            .neighbor_ref(point, dir.offset())
+            .map(|c| c.point)
+            .filter(|p| !self.is_wall(p))
+    }
+
+    /// Moves `id` one step if every cell it would cover is free. Returns whether it moved.
+    pub fn try_move(&mut self, id: EntityId, dir: &Dir) -> bool {
+        let blocked = self.entities[id].points().any(|p| {
+            self.target(&p, dir)
+                .is_none_or(|t| self.at(&t).is_some_and(|other| other != id))
+        });
+        if blocked {
+            return false;
+        }
+        self.shift(&[id], dir);
+        true
+    }
+
+    /// Every entity that moves when `id` pushes in `dir`, `None` if a wall stops the chain.
+    pub fn pushed(&self, id: EntityId, dir: &Dir) -> Option<Vec<EntityId>> {
+        let mut chain = vec![id];
+        let mut i = 0;
+        while let Some(&current) = chain.get(i) {
+            for p in self.entities[current].points() {
+                let target = self.target(&p, dir)?;
+                if let Some(other) = self.at(&target) {
+                    if !chain.contains(&other) {
+                        chain.push(other);
+                    }
+                }
+            }
+            i += 1;
+        }
+        Some(chain)
+    }
+
+    /// Moves `id` one step, pushing whatever it runs into along, boxes pushing boxes.
+    /// Nothing moves if any entity in the chain would hit a wall. Returns whether it moved.
+    pub fn push(&mut self, id: EntityId, dir: &Dir) -> bool {
+        match self.pushed(id, dir) {
+            Some(chain) => {
+                self.shift(&chain, dir);
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn shift(&mut self, ids: &[EntityId], dir: &Dir) {
+        for &id in ids {
+            for p in self.entities[id].points() {
+                self.occupied.remove(&p);
+            }
+        }
+        for &id in ids {
+            let origin = self.entities[id].origin.moved(dir, &1);
+            self.entities[id].origin = origin;
+            for p in self.entities[id].points() {
+                self.occupied.insert(p, id);
+            }
+        }
+    }
+
+    /// Draws the terrain with the entities on top. `entity` gets the entity and the offset
+    /// of the drawn cell within it.
+    pub fn render(
+        &self,
+        terrain: impl Fn(&T) -> char,
+        entity: impl Fn(&Entity<K>, usize) -> char,
+    ) -> String {
+        let mut out = String::new();
+        for (y, row) in self.terrain.cells.iter().enumerate() {
+            for (x, t) in row.iter().enumerate() {
+                let c = match self.at(&Point { x, y }) {
+                    Some(id) => {
+                        let e = &self.entities[id];
+                        entity(e, x - e.origin.x)
+                    }
+                    None => terrain(t),
+                };
+                out.push(c);
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    enum Kind {
+        Robot,
+        Box,
+    }
+
+    // warehouse from 2024 day 15, boxes `scale` cells wide
+    fn simulate(map: &str, moves: &str, scale: usize) -> (String, usize) {
+        let terrain = Matrix::from_fn(
+            map.lines().next().unwrap().len() * scale,
+            map.lines().count(),
+            |p| map.lines().nth(p.y).unwrap().as_bytes()[p.x / scale] == b'#',
+        );
+        let mut layer = EntityLayer::new(&terrain, |wall| *wall);
+        let mut robot = None;
+        for (y, line) in map.lines().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                let origin = Point { x: x * scale, y };
+                match c {
+                    '@' => robot = layer.spawn(Kind::Robot, origin, 1),
+                    'O' => {
+                        layer.spawn(Kind::Box, origin, scale).unwrap();
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let robot = robot.unwrap();
+        for m in moves.chars() {
+            let dir = match m {
+                '^' => Dir::N,
+                '>' => Dir::E,
+                'v' => Dir::S,
+                _ => Dir::W,
+            };
+            layer.push(robot, &dir);
+        }
+
+        let gps = layer
+            .entities()
+            .filter(|(_, e)| e.kind == Kind::Box)
+            .map(|(_, e)| 100 * e.origin.y + e.origin.x)
+            .sum();
+        let rendered = layer.render(
+            |wall| if *wall { '#' } else { '.' },
+            |e, offset| match (e.kind, e.width, offset) {
+                (Kind::Robot, _, _) => '@',
+                (Kind::Box, 1, _) => 'O',
+                (Kind::Box, _, 0) => '[',
+                _ => ']',
+            },
+        );
+        (rendered, gps)
+    }
+
+    #[test]
+    fn pushes_boxes() {
+        let map = "########\n#..O.O.#\n##@.O..#\n#...O..#\n#.#.O..#\n#...O..#\n#......#\n########";
+        let (_, gps) = simulate(map, "<^^>>>vv<v>>v<<", 1);
+        assert_eq!(gps, 2028);
+    }
+
+    #[test]
+    fn pushes_wide_boxes() {
+        let map = "#######\n#...#.#\n#.....#\n#..OO@#\n#..O..#\n#.....#\n#######";
+        let (rendered, gps) = simulate(map, "<vv<<^^<<^^", 2);
+        assert_eq!(
+            rendered,
+            "##############\n##...[].##..##\n##...@.[]...##\n##....[]....##\n\
+             ##..........##\n##..........##\n##############\n"
+        );
+        assert_eq!(gps, 618);
+    }
+
+    #[test]
+    fn moves_without_pushing() {
+        let terrain = Matrix::<char>::try_from("...\n.#.").unwrap();
+        let mut layer = EntityLayer::new(&terrain, |c| *c == '#');
+        let a = layer.spawn('a', Point { x: 0, y: 0 }, 1).unwrap();
+        let b = layer.spawn('b', Point { x: 1, y: 0 }, 1).unwrap();
+        assert_eq!(layer.spawn('c', Point { x: 1, y: 1 }, 1), None);
+
+        assert!(!layer.try_move(a, &Dir::E));
+        assert!(!layer.try_move(b, &Dir::S));
+        assert!(layer.try_move(a, &Dir::S));
+        assert_eq!(layer.at(&Point { x: 0, y: 1 }), Some(a));
+        assert!(layer.is_free(&Point { x: 0, y: 0 }));
+        assert!(!layer.try_move(a, &Dir::W));
+    }
+}
@@ -0,0 +1,374 @@
+use std::{
+    collections::{BinaryHeap, VecDeque},
+    hash::Hash,
+};
+
+use crate::{graph::Graph, hashing::FastHashMap};
+
+/// A partition of the nodes of a [`Graph`] into `side` and the rest.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cut {
+    /// Total weight of the edges crossing the cut.
+    pub weight: u64,
+    /// Node indices on the first side, sorted.
+    pub side: Vec<usize>,
+    /// `(from, to)` node indices of the edges leaving `side`.
+    pub edges: Vec<(usize, usize)>,
+    nodes: usize,
+}
+
+impl Cut {
+    fn new<N: Clone + Eq + Hash>(graph: &Graph<N>, weight: u64, mut side: Vec<usize>) -> Self {
+        side.sort_unstable();
+        let mut in_side = vec![false; graph.len()];
+        for &i in &side {
+            in_side[i] = true;
+        }
+        let edges = side
+            .iter()
+            .flat_map(|&i| {
+                graph
+                    .neighbors(i)
+                    .iter()
+                    .filter(|(j, _)| !in_side[*j])
+                    .map(move |&(j, _)| (i, j))
+            })
+            .collect();
+        Self {
+            weight,
+            side,
+            edges,
+            nodes: graph.len(),
+        }
+    }
+
+    /// Number of nodes on each side.
+    pub fn sizes(&self) -> (usize, usize) {
+        (self.side.len(), self.nodes - self.side.len())
+    }
+}
+
+/// Arcs of a flow network with their remaining capacity. Every arc is stored next to its
+/// reverse arc, so `arc ^ 1` is the arc flow can be pushed back along.
+struct Residual {
+    to: Vec<usize>,
+    capacity: Vec<u64>,
+    arcs: Vec<Vec<usize>>,
+}
+
+impl Residual {
+    fn new<N: Clone + Eq + Hash>(graph: &Graph<N>) -> Self {
+        let mut residual = Self {
+            to: vec![],
+            capacity: vec![],
+            arcs: vec![vec![]; graph.len()],
+        };
+        for i in 0..graph.len() {
+            for &(j, w) in graph.neighbors(i) {
+                for (from, to, capacity) in [(i, j, w), (j, i, 0)] {
+                    residual.arcs[from].push(residual.to.len());
+                    residual.to.push(to);
+                    residual.capacity.push(capacity);
+                }
+            }
+        }
+        residual
+    }
+
+    /// BFS levels from `source` over arcs with capacity left.
+    fn levels(&self, source: usize) -> Vec<Option<usize>> {
+        let mut level = vec![None; self.arcs.len()];
+        level[source] = Some(0);
+        let mut queue = VecDeque::from([source]);
+        while let Some(node) = queue.pop_front() {
+            for &arc in &self.arcs[node] {
+                let next = self.to[arc];
+                if self.capacity[arc] > 0 && level[next].is_none() {
+                    level[next] = Some(level[node].unwrap() + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
+        level
+    }
+
+    /// Pushes up to `limit` along level-increasing arcs (Dinic's blocking flow).
+    fn push(
+        &mut self,
+        node: usize,
+        sink: usize,
+        limit: u64,
+        level: &[Option<usize>],
+        next_arc: &mut [usize],
+    ) -> u64 {
+        if node == sink {
+            return limit;
+        }
+        while next_arc[node] < self.arcs[node].len() {
+            let arc = self.arcs[node][next_arc[node]];
+            let to = self.to[arc];
+            if self.capacity[arc] > 0 && level[to] == level[node].map(|l| l + 1) {
+                let pushed = self.push(to, sink, limit.min(self.capacity[arc]), level, next_arc);
+                if pushed > 0 {
+                    self.capacity[arc] -= pushed;
+                    self.capacity[arc ^ 1] += pushed;
+                    return pushed;
+                }
+            }
+            next_arc[node] += 1;
+        }
+        0
+    }
+
+    /// Augments along one shortest path, returning the amount pushed (Edmonds–Karp step).
+    fn augment(&mut self, source: usize, sink: usize) -> u64 {
+        let mut via = vec![None; self.arcs.len()];
+        let mut queue = VecDeque::from([source]);
+        while let Some(node) = queue.pop_front() {
+            for &arc in &self.arcs[node] {
+                let next = self.to[arc];
+                if self.capacity[arc] > 0 && next != source && via[next].is_none() {
+                    via[next] = Some(arc);
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        let mut path = vec![];
+        let mut node = sink;
+        while node != source {
+            let Some(arc) = via[node] else {
+                return 0;
+            };
+            path.push(arc);
+            node = self.to[arc ^ 1];
+        }
+        let pushed = path.iter().map(|&arc| self.capacity[arc]).min().unwrap();
+        for arc in path {
+            self.capacity[arc] -= pushed;
+            self.capacity[arc ^ 1] += pushed;
+        }
+        pushed
+    }
+}
+
+impl<N: Clone + Eq + Hash> Graph<N> {
+    /// Maximum flow from `source` to `sink` with edge weights as capacities (Dinic), together
+    /// with a minimum `source`-`sink` cut whose side holds `source`.
+    pub fn max_flow(&self, source: usize, sink: usize) -> Cut {
+        let mut residual = Residual::new(self);
+        let mut flow = 0;
+        if source != sink {
+            loop {
+                let level = residual.levels(source);
+                if level[sink].is_none() {
+                    break;
+                }
+                let mut next_arc = vec![0; self.len()];
+                loop {
+                    let pushed = residual.push(source, sink, u64::MAX, &level, &mut next_arc);
+                    if pushed == 0 {
+                        break;
+                    }
+                    flow += pushed;
+                }
+            }
+        }
+        self.source_side(&residual, source, flow)
+    }
+
+    /// Same result as [`Graph::max_flow`] using Edmonds–Karp, which is simpler but
+    /// slower on large networks.
+    pub fn edmonds_karp(&self, source: usize, sink: usize) -> Cut {
+        let mut residual = Residual::new(self);
+        let mut flow = 0;
+        if source != sink {
+            loop {
+                let pushed = residual.augment(source, sink);
+                if pushed == 0 {
+                    break;
+                }
+                flow += pushed;
+            }
+        }
+        self.source_side(&residual, source, flow)
+    }
+
+    fn source_side(&self, residual: &Residual, source: usize, flow: u64) -> Cut {
+        let reachable = residual.levels(source);
+        let side = (0..self.len())
+            .filter(|&i| reachable[i].is_some())
+            .collect();
+        Cut::new(self, flow, side)
+    }
+
+    /// Global minimum cut of an undirected graph (Stoer–Wagner). Every edge has to be present in
+    /// both directions, as [`Graph::add_undirected_edge`] adds it. `None` for fewer than two nodes.
+    pub fn min_cut(&self) -> Option<Cut> {
+        let n = self.len();
+        if n < 2 {
+            return None;
+        }
+
+        let mut adj = (0..n)
+            .map(|i| {
+                let mut weights = FastHashMap::<usize, u64>::default();
+                for &(j, w) in self.neighbors(i).iter().filter(|(j, _)| *j != i) {
+                    *weights.entry(j).or_default() += w;
+                }
+                weights
+            })
+            .collect::<Vec<_>>();
+        let mut groups = (0..n).map(|i| vec![i]).collect::<Vec<_>>();
+        let mut active = (0..n).collect::<Vec<_>>();
+        let mut best: Option<(u64, Vec<usize>)> = None;
+
+        while active.len() > 1 {
+            // maximum adjacency order: repeatedly add the vertex most tightly connected to the set
+            let mut connectivity = vec![0u64; n];
+            let mut added = vec![false; n];
+            let mut heap = BinaryHeap::from([(0, active[0])]);
+            let mut order = vec![];
+            let mut cut_of_phase = 0;
+            while let Some((w, v)) = heap.pop() {
+                if added[v] || w != connectivity[v] {
+                    continue;
+                }
+                added[v] = true;
+                order.push(v);
+                cut_of_phase = w;
+                for (&u, &wu) in &adj[v] {
+                    if !added[u] {
+                        connectivity[u] += wu;
+                        heap.push((connectivity[u], u));
+                    }
+                }
+            }
+
+            if order.len() < active.len() {
+                // disconnected: the reached component is a cut of weight 0
+                let side = order.iter().flat_map(|&v| groups[v].clone()).collect();
+                return Some(Cut::new(self, 0, side));
+            }
+
+            let (s, t) = (order[order.len() - 2], order[order.len() - 1]);
+            if best.as_ref().is_none_or(|(w, _)| cut_of_phase < *w) {
+                best = Some((cut_of_phase, groups[t].clone()));
+            }
+
+            // merge t into s
+            for (u, w) in std::mem::take(&mut adj[t]) {
+                adj[u].remove(&t);
+                if u != s {
+                    *adj[s].entry(u).or_default() += w;
+                    *adj[u].entry(s).or_default() += w;
+                }
+            }
+            let merged = std::mem::take(&mut groups[t]);
+            groups[s].extend(merged);
+            active.retain(|&v| v != t);
+        }
+
+        best.map(|(weight, side)| Cut::new(self, weight, side))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn wiring() -> Graph<&'static str> {
+        // components from 2023 day 25
+        let input = "jqt: rhn xhk nvd\nrsh: frs pzl lsr\nxhk: hfx\ncmg: qnr nvd lhk bvb\n\
+                     rhn: xhk bvb hfx\nbvb: xhk hfx\npzl: lsr hfx nvd\nqnr: nvd\n\
+                     ntq: jqt hfx bvb xhk\nnvd: lhk\nlsr: lhk\nrzs: qnr cmg lsr rsh\nfrs: qnr lhk lsr";
+        let mut graph = Graph::new();
+        for line in input.lines() {
+            let (a, rest) = line.split_once(": ").unwrap();
+            for b in rest.split(' ') {
+                graph.add_undirected_edge(a, b, 1);
+            }
+        }
+        graph
+    }
+
+    fn cut_names(graph: &Graph<&'static str>, cut: &Cut) -> Vec<[&'static str; 2]> {
+        let mut edges = cut
+            .edges
+            .iter()
+            .map(|&(a, b)| {
+                let mut pair = [*graph.node(a), *graph.node(b)];
+                pair.sort_unstable();
+                pair
+            })
+            .collect::<Vec<_>>();
+        edges.sort_unstable();
+        edges
+    }
+
+    #[test]
+    fn finds_global_min_cut() {
+        let graph = wiring();
+        let cut = graph.min_cut().unwrap();
+        assert_eq!(cut.weight, 3);
+        assert_eq!(cut.sizes().0 * cut.sizes().1, 54);
+        assert_eq!(
+            cut_names(&graph, &cut),
+            vec![["bvb", "cmg"], ["hfx", "pzl"], ["jqt", "nvd"]]
+        );
+
+        let mut split = Graph::new();
+        split.add_undirected_edge(0, 1, 5);
+        split.add_node(2);
+        assert_eq!(split.min_cut().unwrap().weight, 0);
+    }
+
+    #[test]
+    fn computes_max_flow() {
+        let graph = wiring();
+        let (cmg, xhk) = (
+            graph.index_of(&"cmg").unwrap(),
+            graph.index_of(&"xhk").unwrap(),
+        );
+        for cut in [graph.max_flow(cmg, xhk), graph.edmonds_karp(cmg, xhk)] {
+            assert_eq!(cut.weight, 3);
+            assert_eq!(cut.sizes(), (9, 6));
+            assert_eq!(cut_names(&graph, &cut).len(), 3);
+        }
+
+        let mut network = Graph::new();
+        for (from, to, capacity) in [
+            ("s", "v1", 16),
+            ("s", "v2", 13),
+            ("v1", "v3", 12),
+            ("v2", "v1", 4),
+            ("v2", "v4", 14),
+            ("v3", "v2", 9),
+            ("v3", "t", 20),
+            ("v4", "v3", 7),
+            ("v4", "t", 4),
+        ] {
+            network.add_edge(from, to, capacity);
+        }
+        let (s, t) = (
+            network.index_of(&"s").unwrap(),
+            network.index_of(&"t").unwrap(),
+        );
+        assert_eq!(network.max_flow(s, t).weight, 23);
+        assert_eq!(network.edmonds_karp(s, t).weight, 23);
+        let cut = network.max_flow(s, t);
+        let crossing = cut
+            .edges
+            .iter()
+            .map(|&(a, b)| {
+                network
+                    .neighbors(a)
+                    .iter()
+                    .find(|(j, _)| *j == b)
+                    .unwrap()
+                    .1
+            })
+            .sum::<u64>();
+        assert_eq!(crossing, 23);
+    }
+}
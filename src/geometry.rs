@@ -0,0 +1,277 @@
+use crate::{linalg::Rational, math::gcd, Dir, Point};
+
+/// `b - a` per coordinate, widened first so points at opposite ends of the `i64` range work.
+fn offset(a: &Point<i64>, b: &Point<i64>) -> (i128, i128) {
+    (b.x as i128 - a.x as i128, b.y as i128 - a.y as i128)
+}
+
+// exact as long as every coordinate is within ±2^62, so the products stay below 2^126
+fn cross(o: &Point<i64>, a: &Point<i64>, b: &Point<i64>) -> i128 {
+    let (ax, ay) = offset(o, a);
+    let (bx, by) = offset(o, b);
+    ax * by - ay * bx
+}
+
+/// Smallest lattice step from `a` towards `b`, i.e. the offset divided by the gcd of its coordinates.
+/// `(0, 0)` if both points are equal.
+pub fn lattice_step(a: &Point<i64>, b: &Point<i64>) -> Point<i128> {
+    let (dx, dy) = offset(a, b);
+    let g = gcd(dx, dy).max(1);
+    Point {
+        x: dx / g,
+        y: dy / g,
+    }
+}
+
+/// `p + k * step`, `None` if that leaves the `i64` range.
+fn step_from(p: &Point<i64>, step: &Point<i128>, k: i128) -> Option<Point<i64>> {
+    Some(Point {
+        x: i64::try_from(p.x as i128 + k * step.x).ok()?,
+        y: i64::try_from(p.y as i128 + k * step.y).ok()?,
+    })
+}
+
+/// Every lattice point on the infinite line through `a` and `b` for which `in_bounds` holds,
+/// ordered along the line. The region has to be convex (e.g. a grid), as the walk in each
+/// direction stops at the first point outside of it.
+pub fn line_points(
+    a: &Point<i64>,
+    b: &Point<i64>,
+    in_bounds: impl Fn(&Point<i64>) -> bool,
+) -> Vec<Point<i64>> {
+    let step = lattice_step(a, b);
+    if step == (Point { x: 0, y: 0 }) {
+        return if in_bounds(a) { vec![*a] } else { vec![] };
+    }
+
+    let mut points = (1..)
+        .map_while(|k| step_from(a, &step, -k).filter(&in_bounds))
+        .collect::<Vec<_>>();
+    points.reverse();
+    points.extend((0..).map_while(|k| step_from(a, &step, k).filter(&in_bounds)));
+    points
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Segment {
+    pub start: Point<i64>,
+    pub end: Point<i64>,
+}
+
+impl Segment {
+    pub fn new(start: Point<i64>, end: Point<i64>) -> Self {
+        Self { start, end }
+    }
+
+    /// Number of lattice points on the segment, both ends included.
+    pub fn lattice_count(&self) -> i128 {
+        let (dx, dy) = offset(&self.start, &self.end);
+        gcd(dx, dy) + 1
+    }
+
+    /// Lattice points on the segment from `start` to `end`, both included.
+    pub fn lattice_points(&self) -> impl Iterator<Item = Point<i64>> {
+        let step = lattice_step(&self.start, &self.end);
+        let start = self.start;
+        // every point lies between the two ends, so it fits in an i64
+        (0..self.lattice_count()).map(move |k| step_from(&start, &step, k).unwrap())
+    }
+
+    fn contains_collinear(&self, p: &Point<i64>) -> bool {
+        (self.start.x.min(self.end.x)..=self.start.x.max(self.end.x)).contains(&p.x)
+            && (self.start.y.min(self.end.y)..=self.start.y.max(self.end.y)).contains(&p.y)
+    }
+
+    pub fn contains(&self, p: &Point<i64>) -> bool {
+        cross(&self.start, &self.end, p) == 0 && self.contains_collinear(p)
+    }
+
+    /// Whether the segments share at least one point, including touching ends and collinear overlaps.
+    pub fn intersects(&self, other: &Segment) -> bool {
+        let d1 = cross(&other.start, &other.end, &self.start).signum();
+        let d2 = cross(&other.start, &other.end, &self.end).signum();
+        let d3 = cross(&self.start, &self.end, &other.start).signum();
+        let d4 = cross(&self.start, &self.end, &other.end).signum();
+
+        if d1 * d2 < 0 && d3 * d4 < 0 {
+            return true;
+        }
+        (d1 == 0 && other.contains_collinear(&self.start))
+            || (d2 == 0 && other.contains_collinear(&self.end))
+            || (d3 == 0 && self.contains_collinear(&other.start))
+            || (d4 == 0 && self.contains_collinear(&other.end))
+    }
+
+    /// The single point where the segments cross, which need not lie on the lattice.
+    /// `None` if they don't meet or are parallel (use [`Segment::intersects`] for collinear overlaps).
+    pub fn intersection(&self, other: &Segment) -> Option<Point<Rational>> {
+        let r = offset(&self.start, &self.end);
+        let s = offset(&other.start, &other.end);
+        let denom = r.0 * s.1 - r.1 * s.0;
+        if denom == 0 {
+            return None;
+        }
+
+        let qp = offset(&self.start, &other.start);
+        let t = Rational::new(qp.0 * s.1 - qp.1 * s.0, denom);
+        let u = Rational::new(qp.0 * r.1 - qp.1 * r.0, denom);
+        let unit = Rational::ZERO..=Rational::ONE;
+        if !unit.contains(&t) || !unit.contains(&u) {
+            return None;
+        }
+
+        Some(Point {
+            x: Rational::from(self.start.x) + t * Rational::new(r.0, 1),
+            y: Rational::from(self.start.y) + t * Rational::new(r.1, 1),
+        })
+    }
+}
+
+/// A simple polygon on the lattice, given by its vertices in order. The last vertex connects back to the first.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polygon {
+    pub vertices: Vec<Point<i64>>,
+}
+
+impl Polygon {
+    pub fn new(vertices: Vec<Point<i64>>) -> Self {
+        Self { vertices }
+    }
+
+    /// Builds the polygon traced by a dig plan, i.e. `(Dir, len)` steps starting at `start`.
+    pub fn from_steps(start: Point<i64>, steps: impl IntoIterator<Item = (Dir, i64)>) -> Self {
+        let mut vertices = vec![start];
+        let mut current = start;
+        for (dir, len) in steps {
+            current = current.moved(&dir, &len);
+            vertices.push(current);
+        }
+        if vertices.len() > 1 && vertices.last() == vertices.first() {
+            vertices.pop();
+        }
+        Self { vertices }
+    }
+
+    pub fn edges(&self) -> impl Iterator<Item = Segment> + '_ {
+        self.vertices
+            .iter()
+            .zip(self.vertices.iter().cycle().skip(1))
+            .map(|(a, b)| Segment::new(*a, *b))
+    }
+
+    /// Twice the enclosed area (shoelace formula), which keeps half-integer areas exact.
+    pub fn double_area(&self) -> i128 {
+        self.edges()
+            .map(|e| e.start.x as i128 * e.end.y as i128 - e.end.x as i128 * e.start.y as i128)
+            .sum::<i128>()
+            .abs()
+    }
+
+    /// Number of lattice points on the outline.
+    pub fn boundary_points(&self) -> i128 {
+        self.edges().map(|e| e.lattice_count() - 1).sum()
+    }
+
+    /// Number of lattice points strictly inside, via Pick's theorem: `A = I + B/2 - 1`.
+    pub fn interior_points(&self) -> i128 {
+        (self.double_area() - self.boundary_points() + 2) / 2
+    }
+
+    /// Number of lattice points inside or on the outline, e.g. the cubic meters of a dug-out lagoon.
+    pub fn lattice_points(&self) -> i128 {
+        self.interior_points() + self.boundary_points()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn p(x: i64, y: i64) -> Point<i64> {
+        Point { x, y }
+    }
+
+    #[test]
+    fn walks_lattice_lines() {
+        let segment = Segment::new(p(0, 0), p(6, 4));
+        assert_eq!(
+            segment.lattice_points().collect::<Vec<_>>(),
+            vec![p(0, 0), p(3, 2), p(6, 4)]
+        );
+
+        let in_grid = |q: &Point<i64>| (0..10).contains(&q.x) && (0..10).contains(&q.y);
+        assert_eq!(
+            line_points(&p(4, 4), &p(6, 6), in_grid),
+            (0..10).map(|i| p(i, i)).collect::<Vec<_>>()
+        );
+        assert_eq!(line_points(&p(0, 0), &p(3, 1), in_grid).len(), 4);
+
+        // offsets between the ends of the i64 range don't fit in an i64
+        let (min, max) = (p(i64::MIN, i64::MIN), p(i64::MAX, i64::MAX));
+        let diagonal = Segment::new(min, max);
+        assert_eq!(lattice_step(&min, &max), Point { x: 1, y: 1 });
+        assert_eq!(diagonal.lattice_count(), 1 << 64);
+        assert_eq!(line_points(&p(5, 5), &min, in_grid).len(), 10);
+    }
+
+    #[test]
+    fn intersects_segments() {
+        let a = Segment::new(p(0, 0), p(4, 4));
+        let b = Segment::new(p(0, 3), p(3, 0));
+        assert!(a.intersects(&b));
+        assert_eq!(
+            a.intersection(&b),
+            Some(Point {
+                x: Rational::new(3, 2),
+                y: Rational::new(3, 2)
+            })
+        );
+
+        let collinear = Segment::new(p(4, 4), p(6, 6));
+        assert!(a.intersects(&collinear));
+        assert_eq!(a.intersection(&collinear), None);
+        assert!(!a.intersects(&Segment::new(p(5, 0), p(5, 3))));
+        assert!(a.contains(&p(2, 2)));
+
+        // both segments are longer than i64::MAX
+        let big = 1 << 62;
+        let c = Segment::new(p(-big, 0), p(big, 0));
+        let d = Segment::new(p(0, -big), p(0, big));
+        assert!(c.contains(&p(5, 0)));
+        assert!(!c.contains(&p(5, 1)));
+        assert_eq!(
+            c.intersection(&d),
+            Some(Point {
+                x: Rational::ZERO,
+                y: Rational::ZERO
+            })
+        );
+    }
+
+    #[test]
+    fn measures_dig_plans() {
+        // lagoon from 2023 day 18
+        let plan = "R 6,D 5,L 2,D 2,R 2,D 2,L 5,U 2,L 1,U 2,R 2,U 3,L 2,U 2";
+        let steps = plan.split(',').map(|step| {
+            let (dir, len) = step.split_once(' ').unwrap();
+            let dir = match dir {
+                "U" => Dir::N,
+                "R" => Dir::E,
+                "D" => Dir::S,
+                _ => Dir::W,
+            };
+            (dir, len.parse().unwrap())
+        });
+        let lagoon = Polygon::from_steps(p(0, 0), steps);
+        assert_eq!(lagoon.vertices.len(), 14);
+        assert_eq!(lagoon.boundary_points(), 38);
+        assert_eq!(lagoon.double_area(), 84);
+        assert_eq!(lagoon.interior_points(), 24);
+        assert_eq!(lagoon.lattice_points(), 62);
+
+        // products of coordinates this far apart don't fit in an i64
+        let big = 3_000_000_000;
+        let square = Polygon::new(vec![p(-big, -big), p(big, -big), p(big, big), p(-big, big)]);
+        assert_eq!(square.double_area(), 2 * (2 * big as i128).pow(2));
+    }
+}
@@ -0,0 +1,300 @@
+use std::{collections::VecDeque, hash::Hash};
+
+use crate::{hashing::FastHashMap, Dir, Matrix, Point, CARDINALS, ORDINALS};
+
+/// A directed graph with weighted edges between labelled nodes. Nodes are addressed by the index
+/// [`Graph::add_node`] returns; add undirected edges with [`Graph::add_undirected_edge`].
+#[derive(Debug, Clone)]
+pub struct Graph<N> {
+    nodes: Vec<N>,
+    index: FastHashMap<N, usize>,
+    edges: Vec<Vec<(usize, u64)>>,
+}
+
+impl<N: Clone + Eq + Hash> Graph<N> {
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![],
+            index: FastHashMap::default(),
+            edges: vec![],
+        }
+    }
+
+    /// Index of `node`, adding it first if it is new.
+    pub fn add_node(&mut self, node: N) -> usize {
+        if let Some(&i) = self.index.get(&node) {
+            return i;
+        }
+        self.nodes.push(node.clone());
+        self.edges.push(vec![]);
+        self.index.insert(node, self.nodes.len() - 1);
+        self.nodes.len() - 1
+    }
+
+    pub fn add_edge(&mut self, from: N, to: N, weight: u64) {
+        let (from, to) = (self.add_node(from), self.add_node(to));
+        self.edges[from].push((to, weight));
+    }
+
+    pub fn add_undirected_edge(&mut self, a: N, b: N, weight: u64) {
+        self.add_edge(a.clone(), b.clone(), weight);
+        self.add_edge(b, a, weight);
+    }
+
+    pub fn index_of(&self, node: &N) -> Option<usize> {
+        self.index.get(node).copied()
+    }
+
+    pub fn node(&self, i: usize) -> &N {
+        &self.nodes[i]
+    }
+
+    pub fn nodes(&self) -> &[N] {
+        &self.nodes
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Outgoing `(target, weight)` edges of node `i`.
+    pub fn neighbors(&self, i: usize) -> &[(usize, u64)] {
+        &self.edges[i]
+    }
+
+    /// Distances in edge counts from `start` to every node, ignoring weights.
+    pub fn bfs(&self, start: usize) -> Vec<Option<u64>> {
+        let mut dist = vec![None; self.len()];
+        dist[start] = Some(0);
+        let mut queue = VecDeque::from([start]);
+        while let Some(node) = queue.pop_front() {
+            let d = dist[node].unwrap();
+            for &(next, _) in &self.edges[node] {
+                if dist[next].is_none() {
+                    dist[next] = Some(d + 1);
+                    queue.push_back(next);
+                }
+            }
+        }
+        dist
+    }
+
+    /// Edge-count distances between all pairs, one BFS per node. O(n·(n + e)), best for sparse graphs.
+    pub fn all_pairs_bfs(&self) -> Distances {
+        Distances((0..self.len()).map(|i| self.bfs(i)).collect())
+    }
+
+    /// Weighted distances between all pairs (Floyd–Warshall). O(n³), best for small dense graphs.
+    pub fn floyd_warshall(&self) -> Distances {
+        let n = self.len();
+        let mut dist = vec![vec![None; n]; n];
+        for (i, row) in dist.iter_mut().enumerate() {
+            row[i] = Some(0);
+            for &(j, w) in &self.edges[i] {
+                row[j] = Some(row[j].map_or(w, |d: u64| d.min(w)));
+            }
+        }
+        for k in 0..n {
+            let via = dist[k].clone();
+            for row in dist.iter_mut() {
+                let Some(to_k) = row[k] else {
+                    continue;
+                };
+                for (d, from_k) in row.iter_mut().zip(&via) {
+                    if let Some(from_k) = from_k {
+                        let candidate = to_k + from_k;
+                        if d.is_none_or(|d| candidate < d) {
+                            *d = Some(candidate);
+                        }
+                    }
+                }
+            }
+        }
+        Distances(dist)
+    }
+
+    /// Every shortest path from `from` to `to` as node indices, both ends included.
+    /// `distances` has to match the edge weights: [`Graph::floyd_warshall`] always does,
+    /// [`Graph::all_pairs_bfs`] only if every weight is `1`. Paths never visit a node twice,
+    /// so cycles of zero-weight edges don't make the number of paths infinite.
+    pub fn all_shortest_paths(
+        &self,
+        distances: &Distances,
+        from: usize,
+        to: usize,
+    ) -> Vec<Vec<usize>> {
+        let mut paths = vec![];
+        if distances.get(from, to).is_some() {
+            let mut path = vec![from];
+            self.extend_paths(distances, to, &mut path, &mut paths);
+        }
+        paths
+    }
+
+    fn extend_paths(
+        &self,
+        distances: &Distances,
+        to: usize,
+        path: &mut Vec<usize>,
+        paths: &mut Vec<Vec<usize>>,
+    ) {
+        let current = *path.last().unwrap();
+        if current == to {
+            paths.push(path.clone());
+            return;
+        }
+        let remaining = distances.get(current, to).unwrap();
+        for &(next, w) in &self.edges[current] {
+            // zero weight edges don't make progress, so only cycle protection stops them
+            if w <= remaining
+                && distances.get(next, to) == Some(remaining - w)
+                && !path.contains(&next)
+            {
+                path.push(next);
+                self.extend_paths(distances, to, path, paths);
+                path.pop();
+            }
+        }
+    }
+}
+
+impl<N: Clone + Eq + Hash> Default for Graph<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Graph<Point> {
+    /// A graph of the cells of `matrix` for which `passable` holds, with unit edges between
+    /// neighboring cells.
+    pub fn from_matrix<T>(
+        matrix: &Matrix<T>,
+        include_ordinals: bool,
+        passable: impl Fn(&T) -> bool,
+    ) -> Self {
+        let mut directions = Vec::from(CARDINALS);
+        if include_ordinals {
+            directions.extend(ORDINALS);
+        }
+
+        let mut graph = Self::new();
+        for cell in matrix.items_ref().filter(|c| passable(c.val)) {
+            graph.add_node(cell.point);
+            for dir in &directions {
+                if let Some(next) = matrix.neighbor_ref(&cell.point, dir.offset()) {
+                    if passable(next.val) {
+                        graph.add_edge(cell.point, next.point, 1);
+                    }
+                }
+            }
+        }
+        graph
+    }
+
+    /// The moves along a path of node indices, e.g. to type a code on a keypad.
+    pub fn path_dirs(&self, path: &[usize]) -> Vec<Dir> {
+        to_dirs(&path.iter().map(|&i| self.nodes[i]).collect::<Vec<_>>())
+    }
+}
+
+/// The direction of every step between consecutive, adjacent points.
+pub fn to_dirs(points: &[Point]) -> Vec<Dir> {
+    points
+        .windows(2)
+        .map(|w| {
+            let offset = (
+                w[1].x as isize - w[0].x as isize,
+                w[1].y as isize - w[0].y as isize,
+            );
+            *CARDINALS
+                .iter()
+                .chain(&ORDINALS)
+                .find(|d| d.offset() == offset)
+                .unwrap_or_else(|| panic!("{:?} and {:?} are not adjacent", w[0], w[1]))
+        })
+        .collect()
+}
+
+/// Distances between every pair of nodes of a [`Graph`], `None` where there is no path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Distances(Vec<Vec<Option<u64>>>);
+
+impl Distances {
+    pub fn get(&self, from: usize, to: usize) -> Option<u64> {
+        self.0[from][to]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keypad() -> (Matrix, Graph<Point>) {
+        let keys = Matrix::<char>::try_from("789\n456\n123\n#0A").unwrap();
+        let graph = Graph::from_matrix(&keys, false, |c| *c != '#');
+        (keys, graph)
+    }
+
+    fn key(keys: &Matrix, graph: &Graph<Point>, c: char) -> usize {
+        let cell = keys.items().find(|cell| cell.val == c).unwrap();
+        graph.index_of(&cell.point).unwrap()
+    }
+
+    #[test]
+    fn computes_all_pairs_distances() {
+        let (keys, graph) = keypad();
+        let bfs = graph.all_pairs_bfs();
+        assert_eq!(bfs, graph.floyd_warshall());
+        assert_eq!(
+            bfs.get(key(&keys, &graph, 'A'), key(&keys, &graph, '7')),
+            Some(5)
+        );
+
+        let mut weighted = Graph::new();
+        weighted.add_edge("a", "b", 4);
+        weighted.add_edge("a", "c", 1);
+        weighted.add_edge("c", "b", 2);
+        weighted.add_edge("b", "d", 1);
+        let dist = weighted.floyd_warshall();
+        let [a, b, d] = ["a", "b", "d"].map(|n| weighted.index_of(&n).unwrap());
+        assert_eq!(dist.get(a, d), Some(4));
+        assert_eq!(dist.get(d, a), None);
+        assert_eq!(weighted.all_shortest_paths(&dist, a, b).len(), 1);
+
+        // teleporters: free moves between "a" and "b" in both directions
+        let mut portals = Graph::new();
+        portals.add_undirected_edge("a", "b", 0);
+        portals.add_edge("a", "c", 1);
+        portals.add_edge("b", "c", 1);
+        let dist = portals.floyd_warshall();
+        let [a, b, c] = ["a", "b", "c"].map(|n| portals.index_of(&n).unwrap());
+        assert_eq!(dist.get(b, c), Some(1));
+        assert_eq!(
+            portals.all_shortest_paths(&dist, a, c),
+            vec![vec![a, b, c], vec![a, c]]
+        );
+    }
+
+    #[test]
+    fn enumerates_shortest_paths() {
+        let (keys, graph) = keypad();
+        let dist = graph.all_pairs_bfs();
+        let (a, seven) = (key(&keys, &graph, 'A'), key(&keys, &graph, '7'));
+
+        // 10 orderings of `<<^^^`, minus the one that crosses the gap
+        let paths = graph.all_shortest_paths(&dist, a, seven);
+        assert_eq!(paths.len(), 9);
+        assert!(paths
+            .iter()
+            .all(|p| graph.path_dirs(p).iter().filter(|d| **d == Dir::W).count() == 2));
+
+        let zero = key(&keys, &graph, '0');
+        let paths = graph.all_shortest_paths(&dist, a, zero);
+        assert_eq!(graph.path_dirs(&paths[0]), vec![Dir::W]);
+        assert_eq!(graph.all_shortest_paths(&dist, a, a), vec![vec![a]]);
+    }
+}
@@ -0,0 +1,270 @@
+use crate::{parse::ParseError, Cell, Dir, Matrix, Point, CARDINALS, ORDINALS};
+
+/// A grid borrowed straight from the puzzle input. Rows are found by stepping over the line
+/// length plus the line break, so nothing is copied until [`GridView::to_matrix`] is called.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GridView<'a> {
+    bytes: &'a [u8],
+    stride: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl<'a> GridView<'a> {
+    /// Wraps `input`, which must consist of lines of equal width separated by `\n` or `\r\n`.
+    /// Blank lines before and after the grid are skipped; a blank line inside it is reported
+    /// like any other line of the wrong width, as rows have to be evenly spaced in the input.
+    pub fn new(input: &'a str) -> Result<Self, ParseError> {
+        let is_break = |c| c == '\n' || c == '\r';
+        let body = input
+            .trim_start_matches(is_break)
+            .trim_end_matches(is_break);
+        let skipped_lines = input[..input.len() - input.trim_start_matches(is_break).len()]
+            .matches('\n')
+            .count();
+        let bytes = body.as_bytes();
+        let width = bytes
+            .iter()
+            .position(|&b| b == b'\n' || b == b'\r')
+            .unwrap_or(bytes.len());
+        let stride = match bytes.get(width) {
+            Some(b'\r') => width + 2,
+            _ => width + 1,
+        };
+        let height = if bytes.is_empty() {
+            0
+        } else {
+            (bytes.len() + stride - width) / stride
+        };
+
+        let mut lines = body.split('\n').enumerate().peekable();
+        while let Some((i, raw)) = lines.next() {
+            let line = raw.strip_suffix('\r').unwrap_or(raw);
+            let expected = if line.len() != width {
+                format!("a line of width {width}")
+            } else if lines.peek().is_some() && raw.len() + 1 != stride {
+                "the same line breaks as the first line".to_string()
+            } else {
+                continue;
+            };
+            return Err(ParseError::new(line, line, expected).with_line(skipped_lines + i + 1));
+        }
+
+        Ok(Self {
+            bytes,
+            stride,
+            width,
+            height,
+        })
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<u8> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        self.bytes.get(y * self.stride + x).copied()
+    }
+
+    /// The bytes of row `y`, without the line break.
+    pub fn get_row(&self, y: usize) -> Option<&'a [u8]> {
+        if y >= self.height {
+            return None;
+        }
+        let start = y * self.stride;
+        Some(&self.bytes[start..start + self.width])
+    }
+
+    pub fn get_cell(&self, x: usize, y: usize) -> Option<Cell<u8>> {
+        self.get(x, y).map(|val| Cell {
+            point: Point { x, y },
+            val,
+        })
+    }
+
+    pub fn items(&self) -> impl Iterator<Item = Cell<u8>> + '_ {
+        (0..self.width)
+            .flat_map(move |x| (0..self.height).map(move |y| self.get_cell(x, y).unwrap()))
+    }
+
+    /// First cell holding `val`, scanning the input in reading order.
+    pub fn find(&self, val: u8) -> Option<Cell<u8>> {
+        let i = self.bytes.iter().position(|&b| b == val)?;
+        self.get_cell(i % self.stride, i / self.stride)
+    }
+
+    /// Value at a position that may lie outside the grid on any side.
+    pub fn get_signed(&self, x: isize, y: isize) -> Option<u8> {
+        self.get(usize::try_from(x).ok()?, usize::try_from(y).ok()?)
+    }
+
+    /// Cell at `offset` from `point`, `None` if that falls outside the grid.
+    pub fn neighbor_at(&self, point: &Point, (dx, dy): (isize, isize)) -> Option<Cell<u8>> {
+        let x = point.x.checked_add_signed(dx)?;
+        let y = point.y.checked_add_signed(dy)?;
+        self.get_cell(x, y)
+    }
+
+    pub fn neighbor(&self, cell: &Cell<u8>, dir: &Dir) -> Option<Cell<u8>> {
+        self.neighbor_at(&cell.point, dir.offset())
+    }
+
+    pub fn neighbors<'b, 'c: 'b>(
+        &'b self,
+        start: &'c Cell<u8>,
+        directions: &'c [Dir],
+    ) -> impl Iterator<Item = (Dir, Option<Cell<u8>>)> + 'b {
+        directions
+            .iter()
+            .map(move |dir| (*dir, self.neighbor(start, dir)))
+    }
+
+    pub fn all_neighbors(
+        &self,
+        start: Cell<u8>,
+        include_ordinals: bool,
+    ) -> impl Iterator<Item = (Dir, Option<Cell<u8>>)> + '_ {
+        let mut neighbors = Vec::from(CARDINALS);
+
+        if include_ordinals {
+            neighbors.extend(Vec::from(ORDINALS));
+        }
+
+        neighbors
+            .into_iter()
+            .map(move |dir| (dir, self.neighbor(&start, &dir)))
+    }
+
+    /// Copies the grid into an owned, mutable matrix.
+    pub fn to_matrix(&self) -> Matrix<u8> {
+        let cells = (0..self.height)
+            .map(|y| self.get_row(y).unwrap().to_vec())
+            .collect();
+        Matrix {
+            cells,
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+/// A grid that reads from the borrowed input until the first write, which promotes it to an owned
+/// [`Matrix<u8>`]. Solutions that only sometimes mutate don't pay for the copy up front.
+#[derive(Debug, Clone)]
+pub enum CowGrid<'a> {
+    Borrowed(GridView<'a>),
+    Owned(Matrix<u8>),
+}
+
+impl<'a> CowGrid<'a> {
+    pub fn new(input: &'a str) -> Result<Self, ParseError> {
+        GridView::new(input).map(CowGrid::Borrowed)
+    }
+
+    pub fn is_owned(&self) -> bool {
+        matches!(self, CowGrid::Owned(_))
+    }
+
+    pub fn width(&self) -> usize {
+        match self {
+            CowGrid::Borrowed(view) => view.width,
+            CowGrid::Owned(matrix) => matrix.width,
+        }
+    }
+
+    pub fn height(&self) -> usize {
+        match self {
+            CowGrid::Borrowed(view) => view.height,
+            CowGrid::Owned(matrix) => matrix.height,
+        }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<u8> {
+        match self {
+            CowGrid::Borrowed(view) => view.get(x, y),
+            CowGrid::Owned(matrix) => matrix.get(x, y),
+        }
+    }
+
+    /// The owned matrix, copying the input on first use.
+    pub fn to_mut(&mut self) -> &mut Matrix<u8> {
+        if let CowGrid::Borrowed(view) = self {
+            *self = CowGrid::Owned(view.to_matrix());
+        }
+        match self {
+            CowGrid::Owned(matrix) => matrix,
+            CowGrid::Borrowed(_) => unreachable!(),
+        }
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut u8> {
+        self.to_mut().get_mut(x, y)
+    }
+
+    pub fn into_matrix(self) -> Matrix<u8> {
+        match self {
+            CowGrid::Borrowed(view) => view.to_matrix(),
+            CowGrid::Owned(matrix) => matrix,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn views_input_bytes() {
+        for input in ["ab.\n#cd\n", "ab.\r\n#cd\r\n", "ab.\n#cd", "\nab.\n#cd\n\n"] {
+            let view = GridView::new(input).unwrap();
+            assert_eq!((view.width, view.height), (3, 2));
+            assert_eq!(view.get(2, 1), Some(b'd'));
+            assert_eq!(view.get(3, 0), None);
+            assert_eq!(view.get_row(1), Some(&b"#cd"[..]));
+            assert_eq!(view.find(b'#').map(|c| c.point), Some(Point { x: 0, y: 1 }));
+
+            let c = view.get_cell(1, 1).unwrap();
+            assert_eq!(view.neighbor(&c, &Dir::N).map(|n| n.val), Some(b'b'));
+            assert_eq!(view.neighbor(&c, &Dir::S), None);
+            assert_eq!(
+                view.all_neighbors(c, true)
+                    .filter(|(_, n)| n.is_some())
+                    .count(),
+                5
+            );
+
+            let matrix = Matrix::<char>::try_from(input).unwrap();
+            assert!(view
+                .items()
+                .zip(matrix.items())
+                .all(|(a, b)| a.point == b.point && a.val as char == b.val));
+        }
+
+        let view = GridView::new("a b\nc  \n").unwrap();
+        assert_eq!((view.width, view.height), (3, 2));
+        assert_eq!(view.get_row(1), Some(&b"c  "[..]));
+
+        let view = GridView::new("\nab\ncd\n").unwrap();
+        assert_eq!((view.width, view.height), (2, 2));
+        assert_eq!(view.get(0, 0), Some(b'a'));
+
+        let err = GridView::new("ab\ncd\ne\n").unwrap_err();
+        assert_eq!((err.line, err.text.as_str()), (Some(3), "e"));
+        assert!(GridView::new("ab\n\ncd\n").is_err());
+        assert!(GridView::new("ab\r\ncd\nef").is_err());
+    }
+
+    #[test]
+    fn promotes_on_write() {
+        let mut grid = CowGrid::new("..\n..\n").unwrap();
+        assert_eq!(grid.get(1, 1), Some(b'.'));
+        assert!(!grid.is_owned());
+
+        *grid.get_mut(1, 1).unwrap() = b'#';
+        assert!(grid.is_owned());
+        assert_eq!(grid.get(1, 1), Some(b'#'));
+        assert_eq!(
+            grid.into_matrix().cells,
+            vec![b"..".to_vec(), b".#".to_vec()]
+        );
+    }
+}
@@ -0,0 +1,93 @@
+use std::{
+    collections::{HashMap, HashSet},
+    hash::{BuildHasherDefault, Hasher},
+};
+
+const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+/// The non-cryptographic hash used by rustc ("FxHash"). Much faster than the default SipHash
+/// for small keys like points and directions, and unseeded, so iteration order is the same on every run.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FxHasher {
+    hash: u64,
+}
+
+impl FxHasher {
+    #[inline]
+    fn add_to_hash(&mut self, word: u64) {
+        self.hash = (self.hash.rotate_left(5) ^ word).wrapping_mul(SEED);
+    }
+}
+
+impl Hasher for FxHasher {
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            self.add_to_hash(u64::from_le_bytes(chunk.try_into().unwrap()));
+        }
+        let rest = chunks.remainder();
+        if !rest.is_empty() {
+            let mut buf = [0; 8];
+            buf[..rest.len()].copy_from_slice(rest);
+            self.add_to_hash(u64::from_le_bytes(buf));
+        }
+    }
+
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.add_to_hash(i.into());
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.add_to_hash(i.into());
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.add_to_hash(i.into());
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.add_to_hash(i);
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.add_to_hash(i as u64);
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+}
+
+pub type FastBuildHasher = BuildHasherDefault<FxHasher>;
+
+/// `HashMap` using [`FxHasher`]. Create with `FastHashMap::default()`.
+pub type FastHashMap<K, V> = HashMap<K, V, FastBuildHasher>;
+
+/// `HashSet` using [`FxHasher`]. Create with `FastHashSet::default()`.
+pub type FastHashSet<T> = HashSet<T, FastBuildHasher>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Dir, Point};
+
+    #[test]
+    fn hashes_deterministically() {
+        let build = || {
+            let mut set = FastHashSet::default();
+            for x in 0..100usize {
+                set.insert((Point { x, y: x * 7 % 13 }, Dir::N));
+            }
+            set.into_iter().collect::<Vec<_>>()
+        };
+        assert_eq!(build(), build());
+        assert_eq!(build().len(), 100);
+    }
+}
@@ -0,0 +1,404 @@
+use std::{fmt::Display, str::FromStr};
+
+use regex::{Captures, Regex};
+
+/* -------------------------------------------------------------------------- */
+/*                                    Lexer                                   */
+/* -------------------------------------------------------------------------- */
+
+/// Typed access to the capture groups of a single pattern match.
+pub struct Args<'a> {
+    captures: &'a Captures<'a>,
+    base: usize,
+    len: usize,
+}
+
+impl Args<'_> {
+    /// Text of the `i`-th capture group (0-based) of the pattern.
+    pub fn str(&self, i: usize) -> Option<&str> {
+        if i >= self.len {
+            return None;
+        }
+        self.captures.get(self.base + 1 + i).map(|m| m.as_str())
+    }
+
+    /// Parses the `i`-th capture group (0-based) of the pattern.
+    pub fn get<T: FromStr>(&self, i: usize) -> Option<T> {
+        self.str(i)?.parse().ok()
+    }
+
+    /// The full text matched by the pattern.
+    pub fn matched(&self) -> &str {
+        self.captures.get(self.base).unwrap().as_str()
+    }
+}
+
+/// A decoded instruction and the byte offset it was found at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token<I> {
+    pub offset: usize,
+    pub instr: I,
+}
+
+type Decoder<I> = Box<dyn Fn(&Args) -> Option<I>>;
+
+/// Collects instruction patterns for a [`Lexer`].
+pub struct LexerBuilder<I> {
+    patterns: Vec<(String, Decoder<I>)>,
+}
+
+impl<I> LexerBuilder<I> {
+    /// Adds a regex pattern. `decode` turns its capture groups into an instruction;
+    /// returning `None` skips the match, e.g. when an argument is out of range.
+    pub fn pattern(mut self, pattern: &str, decode: impl Fn(&Args) -> Option<I> + 'static) -> Self {
+        self.patterns.push((pattern.to_string(), Box::new(decode)));
+        self
+    }
+
+    /// Compiles all patterns into a single alternation.
+    pub fn build(self) -> Result<Lexer<I>, regex::Error> {
+        let mut groups = vec![];
+        let mut base = 1;
+        for (pattern, _) in &self.patterns {
+            // captures_len includes the implicit whole-match group.
+            let len = Regex::new(pattern)?.captures_len() - 1;
+            groups.push((base, len));
+            base += len + 1;
+        }
+
+        let combined = self
+            .patterns
+            .iter()
+            .map(|(p, _)| format!("({p})"))
+            .collect::<Vec<_>>()
+            .join("|");
+
+        Ok(Lexer {
+            regex: Regex::new(&combined)?,
+            decoders: self
+                .patterns
+                .into_iter()
+                .zip(groups)
+                .map(|((_, decode), (base, len))| (base, len, decode))
+                .collect(),
+        })
+    }
+}
+
+/// Scans text for a set of instruction patterns, yielding typed instructions in order of appearance.
+pub struct Lexer<I> {
+    regex: Regex,
+    decoders: Vec<(usize, usize, Decoder<I>)>,
+}
+
+impl<I> Lexer<I> {
+    pub fn builder() -> LexerBuilder<I> {
+        LexerBuilder { patterns: vec![] }
+    }
+
+    pub fn tokens<'a>(&'a self, input: &'a str) -> impl Iterator<Item = Token<I>> + 'a {
+        self.regex.captures_iter(input).filter_map(|captures| {
+            let (base, len, decode) = self
+                .decoders
+                .iter()
+                .find(|(base, _, _)| captures.get(*base).is_some())?;
+            let args = Args {
+                captures: &captures,
+                base: *base,
+                len: *len,
+            };
+            Some(Token {
+                offset: captures.get(0)?.start(),
+                instr: decode(&args)?,
+            })
+        })
+    }
+
+    /// Feeds every instruction into `step`, threading `state` through.
+    pub fn run<S>(&self, input: &str, mut state: S, mut step: impl FnMut(&mut S, Token<I>)) -> S {
+        for token in self.tokens(input) {
+            step(&mut state, token);
+        }
+        state
+    }
+}
+
+/* -------------------------------------------------------------------------- */
+/*                              Register machines                             */
+/* -------------------------------------------------------------------------- */
+
+/// What the machine does after executing an instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Effect {
+    Next,
+    Jump(usize),
+    Output(i64),
+    Halt,
+}
+
+/// An instruction of a register machine. `Display` is used for disassembly and traces.
+pub trait Instruction: Display {
+    fn execute(&self, registers: &mut [i64]) -> Effect;
+}
+
+/// A register machine executing a decoded program until the instruction pointer leaves it.
+#[derive(Debug, Clone)]
+pub struct Vm<I: Instruction> {
+    pub program: Vec<I>,
+    pub registers: Vec<i64>,
+    pub ip: usize,
+    pub output: Vec<i64>,
+    pub steps: usize,
+    trace: Option<Vec<String>>,
+}
+
+impl<I: Instruction> Vm<I> {
+    pub fn new(program: Vec<I>, registers: Vec<i64>) -> Self {
+        Self {
+            program,
+            registers,
+            ip: 0,
+            output: vec![],
+            steps: 0,
+            trace: None,
+        }
+    }
+
+    /// Records every executed instruction with the registers after it ran.
+    pub fn with_trace(mut self) -> Self {
+        self.trace = Some(vec![]);
+        self
+    }
+
+    pub fn trace(&self) -> &[String] {
+        self.trace.as_deref().unwrap_or_default()
+    }
+
+    /// Executes a single instruction. Returns `false` once the machine halted.
+    pub fn step(&mut self) -> bool {
+        let Some(instr) = self.program.get(self.ip) else {
+            return false;
+        };
+
+        let effect = instr.execute(&mut self.registers);
+        self.steps += 1;
+
+        if let Some(trace) = self.trace.as_mut() {
+            trace.push(format!(
+                "{:>4}: {:<16} {:?}",
+                self.ip,
+                instr.to_string(),
+                self.registers
+            ));
+        }
+
+        match effect {
+            Effect::Next => self.ip += 1,
+            Effect::Jump(target) => self.ip = target,
+            Effect::Output(val) => {
+                self.output.push(val);
+                self.ip += 1;
+            }
+            Effect::Halt => {
+                self.ip = self.program.len();
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Runs until the machine halts and returns everything it output.
+    pub fn run(&mut self) -> &[i64] {
+        while self.step() {}
+        &self.output
+    }
+
+    /// Renders the program, one instruction per line.
+    pub fn disassemble(&self) -> String {
+        self.program
+            .iter()
+            .enumerate()
+            .map(|(i, instr)| format!("{i:>4}: {instr}"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Combo operand of the 3-bit computer: literals 0-3 or registers A-C.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Combo {
+    Literal(i64),
+    Register(usize),
+}
+
+impl Combo {
+    fn decode(operand: u8) -> Option<Self> {
+        match operand {
+            0..=3 => Some(Combo::Literal(operand.into())),
+            4..=6 => Some(Combo::Register((operand - 4).into())),
+            _ => None,
+        }
+    }
+
+    fn value(&self, registers: &[i64]) -> i64 {
+        match self {
+            Combo::Literal(n) => *n,
+            Combo::Register(r) => registers[*r],
+        }
+    }
+}
+
+impl Display for Combo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Combo::Literal(n) => write!(f, "{n}"),
+            Combo::Register(r) => write!(f, "{}", ['a', 'b', 'c'][*r]),
+        }
+    }
+}
+
+/// Instruction set of the 3-bit computer with registers A, B and C.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ThreeBit {
+    Adv(Combo),
+    Bxl(i64),
+    Bst(Combo),
+    Jnz(usize),
+    Bxc,
+    Out(Combo),
+    Bdv(Combo),
+    Cdv(Combo),
+}
+
+impl ThreeBit {
+    /// Decodes a program of `opcode, operand` pairs. Jump targets are converted
+    /// from word offsets to instruction indices, so an odd target fails to decode.
+    pub fn decode(program: &[u8]) -> Option<Vec<ThreeBit>> {
+        program
+            .chunks(2)
+            .map(|pair| {
+                let &[opcode, operand] = pair else {
+                    return None;
+                };
+                Some(match opcode {
+                    0 => ThreeBit::Adv(Combo::decode(operand)?),
+                    1 => ThreeBit::Bxl(operand.into()),
+                    2 => ThreeBit::Bst(Combo::decode(operand)?),
+                    3 if operand % 2 == 0 => ThreeBit::Jnz(usize::from(operand) / 2),
+                    4 => ThreeBit::Bxc,
+                    5 => ThreeBit::Out(Combo::decode(operand)?),
+                    6 => ThreeBit::Bdv(Combo::decode(operand)?),
+                    7 => ThreeBit::Cdv(Combo::decode(operand)?),
+                    _ => return None,
+                })
+            })
+            .collect()
+    }
+}
+
+/// `a >> shift`, where shifting by 64 or more (or a negative amount) clears every bit.
+fn shr(a: i64, shift: i64) -> i64 {
+    a.checked_shr(u32::try_from(shift).unwrap_or(u32::MAX))
+        .unwrap_or(0)
+}
+
+impl Instruction for ThreeBit {
+    fn execute(&self, registers: &mut [i64]) -> Effect {
+        match self {
+            ThreeBit::Adv(c) => registers[0] = shr(registers[0], c.value(registers)),
+            ThreeBit::Bxl(n) => registers[1] ^= n,
+            ThreeBit::Bst(c) => registers[1] = c.value(registers) & 7,
+            ThreeBit::Jnz(target) if registers[0] != 0 => return Effect::Jump(*target),
+            ThreeBit::Jnz(_) => {}
+            ThreeBit::Bxc => registers[1] ^= registers[2],
+            ThreeBit::Out(c) => return Effect::Output(c.value(registers) & 7),
+            ThreeBit::Bdv(c) => registers[1] = shr(registers[0], c.value(registers)),
+            ThreeBit::Cdv(c) => registers[2] = shr(registers[0], c.value(registers)),
+        }
+        Effect::Next
+    }
+}
+
+impl Display for ThreeBit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ThreeBit::Adv(c) => write!(f, "a = a >> {c}"),
+            ThreeBit::Bxl(n) => write!(f, "b = b ^ {n}"),
+            ThreeBit::Bst(c) => write!(f, "b = {c} & 7"),
+            ThreeBit::Jnz(target) => write!(f, "jnz {target}"),
+            ThreeBit::Bxc => write!(f, "b = b ^ c"),
+            ThreeBit::Out(c) => write!(f, "out {c} & 7"),
+            ThreeBit::Bdv(c) => write!(f, "b = a >> {c}"),
+            ThreeBit::Cdv(c) => write!(f, "c = a >> {c}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    enum Instr {
+        Mul(u32, u32),
+        Do,
+        Dont,
+    }
+
+    #[test]
+    fn lexes_typed_instructions() {
+        let lexer = Lexer::builder()
+            .pattern(r"mul\((\d{1,3}),(\d{1,3})\)", |args| {
+                Some(Instr::Mul(args.get(0)?, args.get(1)?))
+            })
+            .pattern(r"do\(\)", |_| Some(Instr::Do))
+            .pattern(r"don't\(\)", |_| Some(Instr::Dont))
+            .build()
+            .unwrap();
+
+        let tokens = lexer
+            .tokens("xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+do()")
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tokens,
+            vec![
+                Token {
+                    offset: 1,
+                    instr: Instr::Mul(2, 4)
+                },
+                Token {
+                    offset: 20,
+                    instr: Instr::Dont
+                },
+                Token {
+                    offset: 28,
+                    instr: Instr::Mul(5, 5)
+                },
+                Token {
+                    offset: 37,
+                    instr: Instr::Do
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn runs_three_bit_programs() {
+        let program = ThreeBit::decode(&[0, 1, 5, 4, 3, 0]).unwrap();
+        let mut vm = Vm::new(program, vec![729, 0, 0]).with_trace();
+        assert_eq!(vm.run(), &[4, 6, 3, 5, 6, 3, 5, 2, 1, 0]);
+        assert_eq!(vm.registers[0], 0);
+        assert_eq!(vm.trace().len(), vm.steps);
+        assert_eq!(
+            vm.disassemble(),
+            "   0: a = a >> 1\n   1: out a & 7\n   2: jnz 0"
+        );
+
+        assert_eq!(ThreeBit::decode(&[0, 1, 3, 1]), None);
+
+        // b = a >> b, with b far past the width of a register
+        let program = ThreeBit::decode(&[6, 5, 5, 5]).unwrap();
+        assert_eq!(Vm::new(program, vec![-1, 100, 0]).run(), &[0]);
+    }
+}
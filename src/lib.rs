@@ -1,4 +1,26 @@
+pub mod automaton;
+pub mod backtrack;
+pub mod bisect;
+pub mod collections;
+pub mod compress;
+pub mod entities;
+pub mod flow;
+pub mod geometry;
+pub mod graph;
+pub mod grid;
+pub mod hashing;
+pub mod interpreter;
+pub mod linalg;
+pub mod math;
+pub mod memo;
+pub mod parallel;
+pub mod parse;
+pub mod prefix;
+pub mod ranges;
+pub mod scanner;
+pub mod sequence;
 pub mod template;
+pub mod trie;
 
 use std::{
     fmt::Display,
@@ -7,6 +29,7 @@ use std::{
 };
 
 use itertools::Itertools;
+use parse::ParseError;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Dir {
@@ -33,6 +56,20 @@ impl Dir {
             Dir::SW => Dir::NE,
         }
     }
+
+    /// `(dx, dy)` of one step in this direction, with `y` growing downwards.
+    pub fn offset(&self) -> (isize, isize) {
+        match self {
+            Dir::N => (0, -1),
+            Dir::E => (1, 0),
+            Dir::S => (0, 1),
+            Dir::W => (-1, 0),
+            Dir::NE => (1, -1),
+            Dir::SE => (1, 1),
+            Dir::SW => (-1, 1),
+            Dir::NW => (-1, -1),
+        }
+    }
 }
 
 pub static CARDINALS: [Dir; 4] = [Dir::N, Dir::E, Dir::S, Dir::W];
@@ -85,82 +122,112 @@ impl<T: Copy + Clone + Add<Output = T> + Sub<Output = T>> Point<T> {
     }
 }
 
+/// A value together with its position. Positions are `usize` by default,
+/// use `Cell<T, i64>` where they can leave the grid (e.g. infinite or wrapping maps).
 #[derive(Debug, Clone, Copy, Eq)]
-pub struct Cell<T: Copy + Display = char> {
+pub struct Cell<T = char, P: Clone = usize> {
     pub val: T,
-    pub point: Point,
+    pub point: Point<P>,
 }
 
-impl<T: Copy + Display> PartialEq for Cell<T> {
+impl<T, P: Clone + PartialEq> PartialEq for Cell<T, P> {
     fn eq(&self, other: &Self) -> bool {
         self.point == other.point
     }
 }
 
-impl<T: Copy + Display> Hash for Cell<T> {
+impl<T, P: Clone + Hash> Hash for Cell<T, P> {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.point.hash(state);
     }
 }
 
-impl<T: Copy + Display> Display for Cell<T> {
+impl<T: Display, P: Clone + Display> Display for Cell<T, P> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "[{}:{}] ({})", self.point.x, self.point.y, self.val)
     }
 }
 
-#[derive(Debug)]
-pub struct Matrix<T: Copy = char> {
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Matrix<T = char> {
     pub cells: Vec<Vec<T>>,
     pub width: usize,
     pub height: usize,
 }
 
-impl From<&str> for Matrix<char> {
-    fn from(s: &str) -> Self {
-        let cells: Vec<Vec<char>> = s
-            .lines()
-            .filter_map(|l| {
-                if !l.is_empty() {
-                    Some(l.chars().collect())
-                } else {
-                    None
+impl<T> Matrix<T> {
+    /// Parses a grid, converting every character with `f`.
+    /// Errors point at the first character `f` rejects or at the first line of a different width.
+    pub fn try_parse(
+        s: &str,
+        f: impl Fn(char) -> Option<T>,
+        expected: &str,
+    ) -> Result<Self, ParseError> {
+        let mut cells: Vec<Vec<T>> = vec![];
+
+        for (i, line) in s.lines().enumerate().filter(|(_, l)| !l.is_empty()) {
+            let row = line
+                .char_indices()
+                .map(|(j, c)| {
+                    f(c).ok_or_else(|| {
+                        ParseError::new(line, &line[j..j + c.len_utf8()], expected).with_line(i + 1)
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            if let Some(first) = cells.first() {
+                if row.len() != first.len() {
+                    return Err(ParseError::new(
+                        line,
+                        line,
+                        format!("a line of width {}", first.len()),
+                    )
+                    .with_line(i + 1));
                 }
-            })
-            .collect();
+            }
 
-        Self {
-            width: cells[0].len(),
+            cells.push(row);
+        }
+
+        Ok(Self {
+            width: cells.first().map_or(0, Vec::len),
             height: cells.len(),
             cells,
-        }
+        })
     }
 }
 
-impl From<&str> for Matrix<u32> {
-    fn from(s: &str) -> Self {
-        let cells: Vec<Vec<u32>> = s
-            .lines()
-            .filter_map(|l| {
-                if !l.is_empty() {
-                    Some(l.chars().map(|x| x.to_digit(10).unwrap()).collect())
-                } else {
-                    None
-                }
-            })
-            .collect();
+impl TryFrom<&str> for Matrix<char> {
+    type Error = ParseError;
+
+    fn try_from(s: &str) -> Result<Self, ParseError> {
+        Matrix::try_parse(s, Some, "a character")
+    }
+}
 
+impl TryFrom<&str> for Matrix<u32> {
+    type Error = ParseError;
+
+    fn try_from(s: &str) -> Result<Self, ParseError> {
+        Matrix::try_parse(s, |c| c.to_digit(10), "a digit")
+    }
+}
+
+impl<T> Matrix<T> {
+    /// A `width` by `height` grid with every cell computed from its position.
+    pub fn from_fn(width: usize, height: usize, f: impl Fn(Point) -> T) -> Self {
+        let cells = (0..height)
+            .map(|y| (0..width).map(|x| f(Point { x, y })).collect())
+            .collect();
         Self {
-            width: cells[0].len(),
-            height: cells.len(),
             cells,
+            width,
+            height,
         }
     }
-}
 
-impl<T: Copy + Display> Matrix<T> {
-    pub fn get(&self, x: usize, y: usize) -> Option<T> {
-        self.cells.get(y).and_then(|l| l.get(x).copied())
+    pub fn get_ref(&self, x: usize, y: usize) -> Option<&T> {
+        self.cells.get(y).and_then(|l| l.get(x))
     }
 
     pub fn get_row(&self, y: usize) -> Option<&Vec<T>> {
@@ -175,6 +242,81 @@ impl<T: Copy + Display> Matrix<T> {
         self.cells.get_mut(y).and_then(|l| l.get_mut(x))
     }
 
+    pub fn cell_ref(&self, x: usize, y: usize) -> Option<Cell<&T>> {
+        self.get_ref(x, y).map(|val| Cell {
+            point: Point { x, y },
+            val,
+        })
+    }
+
+    /// Borrowing counterpart of [`Matrix::items`], in the same order.
+    pub fn items_ref(&self) -> impl Iterator<Item = Cell<&T>> + '_ {
+        (0..self.width)
+            .cartesian_product(0..self.height)
+            .map(|(x, y)| self.cell_ref(x, y).unwrap())
+    }
+
+    /// Borrowing counterpart of [`Matrix::neighbor_at`].
+    pub fn neighbor_ref(&self, point: &Point, (dx, dy): (isize, isize)) -> Option<Cell<&T>> {
+        let x = point.x.checked_add_signed(dx)?;
+        let y = point.y.checked_add_signed(dy)?;
+        self.cell_ref(x, y)
+    }
+
+    /// Borrowing counterpart of [`Matrix::all_neighbors`].
+    pub fn all_neighbors_ref(
+        &self,
+        point: Point,
+        include_ordinals: bool,
+    ) -> impl Iterator<Item = (Dir, Option<Cell<&T>>)> + '_ {
+        let mut directions = Vec::from(CARDINALS);
+        if include_ordinals {
+            directions.extend(ORDINALS);
+        }
+        directions
+            .into_iter()
+            .map(move |dir| (dir, self.neighbor_ref(&point, dir.offset())))
+    }
+
+    /// Every cell with a mutable reference to its value, in the same order as [`Matrix::items`].
+    pub fn items_mut(&mut self) -> impl Iterator<Item = Cell<&mut T>> {
+        let mut rows = self
+            .cells
+            .iter_mut()
+            .map(|r| r.iter_mut())
+            .collect::<Vec<_>>();
+        let mut cells = Vec::with_capacity(self.width * self.height);
+        for x in 0..self.width {
+            for (y, row) in rows.iter_mut().enumerate() {
+                if let Some(val) = row.next() {
+                    cells.push(Cell {
+                        point: Point { x, y },
+                        val,
+                    });
+                }
+            }
+        }
+        cells.into_iter()
+    }
+
+    pub fn map<U>(&self, f: impl Fn(&T) -> U) -> Matrix<U> {
+        Matrix {
+            cells: self
+                .cells
+                .iter()
+                .map(|row| row.iter().map(&f).collect())
+                .collect(),
+            width: self.width,
+            height: self.height,
+        }
+    }
+}
+
+impl<T: Copy> Matrix<T> {
+    pub fn get(&self, x: usize, y: usize) -> Option<T> {
+        self.get_ref(x, y).copied()
+    }
+
     pub fn get_cell(&self, x: usize, y: usize) -> Option<Cell<T>> {
         self.get(x, y).map(|val| Cell {
             point: Point { x, y },
@@ -188,81 +330,27 @@ impl<T: Copy + Display> Matrix<T> {
             .map(|(x, y)| self.get_cell(x, y).unwrap())
     }
 
+    /// Value at a position that may lie outside the grid on any side.
+    pub fn get_signed(&self, x: isize, y: isize) -> Option<T> {
+        self.get(usize::try_from(x).ok()?, usize::try_from(y).ok()?)
+    }
+
+    /// Like [`Matrix::get_signed`], keeping the signed position on the returned cell.
+    pub fn get_signed_cell(&self, point: Point<i64>) -> Option<Cell<T, i64>> {
+        let val = self.get_signed(point.x.try_into().ok()?, point.y.try_into().ok()?)?;
+        Some(Cell { val, point })
+    }
+
+    /// Cell at `offset` from `point`, `None` if that falls outside the grid.
+    pub fn neighbor_at(&self, point: &Point, offset: (isize, isize)) -> Option<Cell<T>> {
+        self.neighbor_ref(point, offset).map(|c| Cell {
+            val: *c.val,
+            point: c.point,
+        })
+    }
+
     pub fn neighbor(&self, cell: &Cell<T>, dir: &Dir) -> Option<Cell<T>> {
-        match dir {
-            Dir::NW => {
-                let y = cell.point.y.checked_sub(1)?;
-                let x = cell.point.x.checked_sub(1)?;
-                let val = self.get(x, y)?;
-                Some(Cell {
-                    point: Point { x, y },
-                    val,
-                })
-            }
-            Dir::N => {
-                let x = cell.point.x;
-                let y = cell.point.y.checked_sub(1)?;
-                let val = self.get(x, y)?;
-                Some(Cell {
-                    point: Point { x, y },
-                    val,
-                })
-            }
-            Dir::NE => {
-                let x = cell.point.x + 1;
-                let y = cell.point.y.checked_sub(1)?;
-                let val = self.get(x, y)?;
-                Some(Cell {
-                    point: Point { x, y },
-                    val,
-                })
-            }
-            Dir::W => {
-                let x = cell.point.x.checked_sub(1)?;
-                let y = cell.point.y;
-                let val = self.get(x, y)?;
-                Some(Cell {
-                    point: Point { x, y },
-                    val,
-                })
-            }
-            Dir::E => {
-                let x = cell.point.x + 1;
-                let y = cell.point.y;
-                let val = self.get(x, y)?;
-                Some(Cell {
-                    point: Point { x, y },
-                    val,
-                })
-            }
-            Dir::SW => {
-                let x = cell.point.x.checked_sub(1)?;
-                let y = cell.point.y + 1;
-                let val = self.get(x, y)?;
-                Some(Cell {
-                    point: Point { x, y },
-                    val,
-                })
-            }
-            Dir::S => {
-                let y = cell.point.y + 1;
-                let x = cell.point.x;
-                let val = self.get(x, y)?;
-                Some(Cell {
-                    point: Point { x, y },
-                    val,
-                })
-            }
-            Dir::SE => {
-                let x = cell.point.x + 1;
-                let y = cell.point.y + 1;
-                let val = self.get(x, y)?;
-                Some(Cell {
-                    point: Point { x, y },
-                    val,
-                })
-            }
-        }
+        self.neighbor_at(&cell.point, dir.offset())
     }
 
     pub fn neighbors<'a, 'b: 'a>(
@@ -293,3 +381,74 @@ impl<T: Copy + Display> Matrix<T> {
         })
     }
 }
+
+/// Renders one line per row, without separators between cells.
+impl<T: Display> Display for Matrix<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for row in &self.cells {
+            for val in row {
+                write!(f, "{val}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn addresses_signed_positions() {
+        let matrix = Matrix::<char>::try_from("abc\ndef\n").unwrap();
+        assert!(Matrix::<char>::try_from("abc\nde\n").is_err());
+        assert_eq!(matrix.get_signed(-1, 0), None);
+        assert_eq!(matrix.get_signed(2, 1), Some('f'));
+
+        let b = Point { x: 1, y: 0 };
+        assert_eq!(matrix.neighbor_at(&b, (1, 1)).map(|c| c.val), Some('f'));
+        assert_eq!(matrix.neighbor_at(&b, (-2, 0)), None);
+        assert_eq!(matrix.neighbor_at(&b, (0, 2)), None);
+
+        let cell = matrix.get_signed_cell(Point { x: 0, y: 1 }).unwrap();
+        assert_eq!((cell.val, cell.point), ('d', Point { x: 0i64, y: 1 }));
+        assert_eq!(matrix.get_signed_cell(Point { x: 0, y: -1 }), None);
+
+        let e = matrix.get_cell(1, 1).unwrap();
+        for dir in CARDINALS.iter().chain(&ORDINALS) {
+            let (dx, dy) = dir.offset();
+            let expected = matrix.get_signed(1 + dx, 1 + dy);
+            assert_eq!(matrix.neighbor(&e, dir).map(|c| c.val), expected);
+        }
+    }
+
+    #[test]
+    fn stores_owned_values() {
+        let mut matrix = Matrix::from_fn(3, 2, |p| vec![p.x; p.y]);
+        matrix.get_mut(2, 1).unwrap().push(7);
+        assert_eq!(matrix.get_ref(2, 1), Some(&vec![2, 7]));
+        assert_eq!(
+            matrix
+                .neighbor_ref(&Point { x: 1, y: 1 }, Dir::E.offset())
+                .map(|c| c.val.len()),
+            Some(2)
+        );
+        assert_eq!(matrix.items_ref().filter(|c| c.val.is_empty()).count(), 3);
+        assert_eq!(
+            matrix
+                .all_neighbors_ref(Point { x: 1, y: 1 }, false)
+                .filter_map(|(_, n)| n.map(|n| n.val.len()))
+                .collect::<Vec<_>>(),
+            vec![0, 2, 1]
+        );
+
+        for cell in matrix.items_mut().filter(|c| c.val.is_empty()) {
+            cell.val.push(cell.point.x);
+        }
+        assert_eq!(matrix.get_row(0), Some(&vec![vec![0], vec![1], vec![2]]));
+
+        let lengths = matrix.map(Vec::len);
+        assert_eq!(lengths.to_string(), "111\n112\n");
+    }
+}
@@ -0,0 +1,356 @@
+use std::{
+    cmp::Ordering,
+    fmt::Display,
+    ops::{Add, Div, Mul, Neg, Sub},
+};
+
+use crate::math::gcd;
+
+/// An exact fraction, always stored in lowest terms with a positive denominator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    num: i128,
+    den: i128,
+}
+
+impl Rational {
+    pub const ZERO: Rational = Rational { num: 0, den: 1 };
+    pub const ONE: Rational = Rational { num: 1, den: 1 };
+
+    pub fn new(num: i128, den: i128) -> Self {
+        assert!(den != 0, "denominator must not be zero");
+        let g = gcd(num, den);
+        let sign = den.signum();
+        Self {
+            num: sign * num / g,
+            den: sign * den / g,
+        }
+    }
+
+    pub fn numerator(&self) -> i128 {
+        self.num
+    }
+
+    pub fn denominator(&self) -> i128 {
+        self.den
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.num == 0
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.den == 1
+    }
+
+    /// Returns the value if it is a whole number.
+    pub fn to_integer(&self) -> Option<i128> {
+        self.is_integer().then_some(self.num)
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(n: i64) -> Self {
+        Self {
+            num: n.into(),
+            den: 1,
+        }
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+impl Add for Rational {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        let g = gcd(self.den, rhs.den);
+        Self::new(
+            self.num * (rhs.den / g) + rhs.num * (self.den / g),
+            self.den / g * rhs.den,
+        )
+    }
+}
+
+impl Sub for Rational {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self + -rhs
+    }
+}
+
+impl Mul for Rational {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        // cross-reduce first to keep intermediate values small.
+        let g1 = gcd(self.num, rhs.den).max(1);
+        let g2 = gcd(rhs.num, self.den).max(1);
+        Self::new(
+            (self.num / g1) * (rhs.num / g2),
+            (self.den / g2) * (rhs.den / g1),
+        )
+    }
+}
+
+impl Div for Rational {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self {
+        assert!(!rhs.is_zero(), "division by zero");
+        self * Self::new(rhs.den, rhs.num)
+    }
+}
+
+impl Neg for Rational {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Self {
+            num: -self.num,
+            den: self.den,
+        }
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.num * other.den).cmp(&(other.num * self.den))
+    }
+}
+
+/// Outcome of solving a linear system.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Solution {
+    Unique(Vec<Rational>),
+    Infinite,
+    None,
+}
+
+impl Solution {
+    /// Returns the solution if it is unique and every component is a whole number.
+    pub fn integer(&self) -> Option<Vec<i128>> {
+        match self {
+            Solution::Unique(values) => values.iter().map(Rational::to_integer).collect(),
+            _ => None,
+        }
+    }
+}
+
+/// Solves `a * x = b` for a 2×2 system using Cramer's rule.
+///
+/// Claw machine puzzles are of this shape: `[[ax, bx], [ay, by]] * [presses_a, presses_b] = [px, py]`.
+pub fn solve_2x2(a: [[i64; 2]; 2], b: [i64; 2]) -> Solution {
+    let [[a11, a12], [a21, a22]] = a.map(|row| row.map(i128::from));
+    let [b1, b2] = b.map(i128::from);
+
+    let det = a11 * a22 - a12 * a21;
+    if det == 0 {
+        return solve(&a, &b);
+    }
+
+    Solution::Unique(vec![
+        Rational::new(b1 * a22 - a12 * b2, det),
+        Rational::new(a11 * b2 - b1 * a21, det),
+    ])
+}
+
+/// Solves `a * x = b` for an arbitrary n×m system by Gaussian elimination over rationals.
+pub fn solve<R: AsRef<[i64]>>(a: &[R], b: &[i64]) -> Solution {
+    assert_eq!(
+        a.len(),
+        b.len(),
+        "one right-hand side value per row required"
+    );
+
+    let cols = a.first().map_or(0, |r| r.as_ref().len());
+    let mut rows: Vec<Vec<Rational>> = a
+        .iter()
+        .zip(b)
+        .map(|(row, &rhs)| {
+            row.as_ref()
+                .iter()
+                .copied()
+                .chain([rhs])
+                .map(Rational::from)
+                .collect()
+        })
+        .collect();
+
+    let mut pivot_cols = vec![];
+    let mut pivot_row = 0;
+
+    for col in 0..cols {
+        let Some(found) = (pivot_row..rows.len()).find(|&r| !rows[r][col].is_zero()) else {
+            continue;
+        };
+        rows.swap(pivot_row, found);
+
+        let pivot = rows[pivot_row][col];
+        for v in rows[pivot_row].iter_mut() {
+            *v = *v / pivot;
+        }
+
+        let pivot_values = rows[pivot_row].clone();
+        for (r, row) in rows.iter_mut().enumerate() {
+            let factor = row[col];
+            if r == pivot_row || factor.is_zero() {
+                continue;
+            }
+            for (v, p) in row[col..].iter_mut().zip(&pivot_values[col..]) {
+                *v = *v - factor * *p;
+            }
+        }
+
+        pivot_cols.push(col);
+        pivot_row += 1;
+    }
+
+    // a row of zeros with a non-zero right-hand side is a contradiction.
+    if rows[pivot_row..].iter().any(|r| !r[cols].is_zero()) {
+        return Solution::None;
+    }
+
+    if pivot_cols.len() < cols {
+        return Solution::Infinite;
+    }
+
+    Solution::Unique(rows[..cols].iter().map(|r| r[cols]).collect())
+}
+
+/// Multiplies two square matrices modulo `modulus`.
+pub fn mat_mul(a: &[Vec<u64>], b: &[Vec<u64>], modulus: u64) -> Vec<Vec<u64>> {
+    let n = a.len();
+    let m = u128::from(modulus);
+    (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| {
+                    let sum = (0..n).fold(0u128, |acc, k| {
+                        (acc + u128::from(a[i][k]) * u128::from(b[k][j])) % m
+                    });
+                    sum as u64
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// Raises a square matrix to `exp` modulo `modulus` by repeated squaring.
+pub fn mat_pow(matrix: &[Vec<u64>], mut exp: u64, modulus: u64) -> Vec<Vec<u64>> {
+    let n = matrix.len();
+    let mut result: Vec<Vec<u64>> = (0..n)
+        .map(|i| (0..n).map(|j| u64::from(i == j) % modulus).collect())
+        .collect();
+    let mut base = matrix.to_vec();
+
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mat_mul(&result, &base, modulus);
+        }
+        base = mat_mul(&base, &base, modulus);
+        exp >>= 1;
+    }
+
+    result
+}
+
+/// Computes the `n`-th term (0-based) of the linear recurrence
+/// `x[k] = coeffs[0] * x[k - 1] + coeffs[1] * x[k - 2] + ...` modulo `modulus`.
+pub fn linear_recurrence(coeffs: &[u64], initial: &[u64], n: u64, modulus: u64) -> u64 {
+    let k = coeffs.len();
+    assert_eq!(
+        k,
+        initial.len(),
+        "one initial value per coefficient required"
+    );
+
+    if n < k as u64 {
+        return initial[n as usize] % modulus;
+    }
+
+    // companion matrix: first row holds the coefficients, the rest shifts the state down.
+    let companion: Vec<Vec<u64>> = (0..k)
+        .map(|i| {
+            if i == 0 {
+                coeffs.iter().map(|c| c % modulus).collect()
+            } else {
+                (0..k).map(|j| u64::from(j + 1 == i)).collect()
+            }
+        })
+        .collect();
+
+    let power = mat_pow(&companion, n - (k as u64 - 1), modulus);
+    let m = u128::from(modulus);
+    (0..k).fold(0u128, |acc, j| {
+        (acc + u128::from(power[0][j]) * u128::from(initial[k - 1 - j] % modulus)) % m
+    }) as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rational_arithmetic() {
+        let a = Rational::new(1, 2);
+        let b = Rational::new(-2, 6);
+        assert_eq!(a + b, Rational::new(1, 6));
+        assert_eq!(a * b, Rational::new(-1, 6));
+        assert_eq!(a / b, Rational::new(-3, 2));
+        assert!(b < a);
+        assert_eq!(Rational::new(4, -2).to_integer(), Some(-2));
+        assert_eq!(
+            Rational::new(i128::MIN, 4).to_integer(),
+            Some(i128::MIN / 4)
+        );
+    }
+
+    #[test]
+    fn solves_claw_machines() {
+        let solution = solve_2x2([[94, 22], [34, 67]], [8400, 5400]);
+        assert_eq!(solution.integer(), Some(vec![80, 40]));
+
+        let solution = solve_2x2([[26, 67], [66, 21]], [12748, 12176]);
+        assert!(matches!(solution, Solution::Unique(_)));
+        assert_eq!(solution.integer(), None);
+    }
+
+    #[test]
+    fn solves_general_systems() {
+        let a = [vec![2, 1, -1], vec![-3, -1, 2], vec![-2, 1, 2]];
+        assert_eq!(solve(&a, &[8, -11, -3]).integer(), Some(vec![2, 3, -1]));
+
+        let singular = [vec![1, 2], vec![2, 4]];
+        assert_eq!(solve(&singular, &[3, 6]), Solution::Infinite);
+        assert_eq!(solve(&singular, &[3, 7]), Solution::None);
+    }
+
+    #[test]
+    fn computes_linear_recurrences() {
+        assert_eq!(linear_recurrence(&[1, 1], &[0, 1], 10, u64::MAX), 55);
+        assert_eq!(
+            linear_recurrence(&[1, 1], &[0, 1], 90, u64::MAX),
+            2880067194370816120
+        );
+        assert_eq!(
+            linear_recurrence(&[1, 1], &[0, 1], 1000, 1_000_000_007),
+            517691607
+        );
+    }
+}
@@ -0,0 +1,385 @@
+use std::{
+    fmt::{self, Debug, Display},
+    ops::{Add, Div, Mul, Neg, Rem, Sub},
+};
+
+/// Primitive integer types supported by the number theory helpers.
+pub trait Integer:
+    Copy
+    + Ord
+    + Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+    const TEN: Self;
+    const MIN: Self;
+    const MAX: Self;
+
+    fn abs(self) -> Self;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+
+    fn checked_sub(self, other: Self) -> Option<Self>;
+
+    fn checked_mul(self, other: Self) -> Option<Self>;
+
+    fn checked_pow(self, exp: u32) -> Option<Self>;
+
+    /// Number of decimal digits of `|self|`, `0` has one digit.
+    fn digit_count(self) -> u32;
+
+    fn to_i128(self) -> i128;
+
+    fn from_i128(n: i128) -> Option<Self>;
+}
+
+/// Marker for signed integers, which are required wherever negative Bézout coefficients show up.
+pub trait Signed: Integer + Neg<Output = Self> {}
+
+macro_rules! impl_integer {
+    (unsigned: $($t:ty),*) => {
+        $( impl_integer!(@impl $t, |n| n); )*
+    };
+    (signed: $($t:ty),*) => {
+        $( impl_integer!(@impl $t, <$t>::abs); )*
+    };
+    (@impl $t:ty, $abs:expr) => {
+        impl Integer for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+            const TEN: Self = 10;
+            const MIN: Self = <$t>::MIN;
+            const MAX: Self = <$t>::MAX;
+
+            fn abs(self) -> Self {
+                $abs(self)
+            }
+
+            fn checked_add(self, other: Self) -> Option<Self> {
+                <$t>::checked_add(self, other)
+            }
+
+            fn checked_sub(self, other: Self) -> Option<Self> {
+                <$t>::checked_sub(self, other)
+            }
+
+            fn checked_mul(self, other: Self) -> Option<Self> {
+                <$t>::checked_mul(self, other)
+            }
+
+            fn checked_pow(self, exp: u32) -> Option<Self> {
+                <$t>::checked_pow(self, exp)
+            }
+
+            fn digit_count(self) -> u32 {
+                self.abs_diff(0).checked_ilog10().map_or(1, |d| d + 1)
+            }
+
+            fn to_i128(self) -> i128 {
+                self as i128
+            }
+
+            fn from_i128(n: i128) -> Option<Self> {
+                <$t>::try_from(n).ok()
+            }
+        }
+    };
+}
+
+impl_integer!(unsigned: u8, u16, u32, u64, usize);
+impl_integer!(signed: i8, i16, i32, i64, i128, isize);
+
+impl Signed for i8 {}
+impl Signed for i16 {}
+impl Signed for i32 {}
+impl Signed for i64 {}
+impl Signed for i128 {}
+impl Signed for isize {}
+
+/// Greatest common divisor, always non-negative.
+/// Panics if it doesn't fit `T`, which only happens for `gcd(T::MIN, 0)` and `gcd(T::MIN, T::MIN)`
+/// of a signed `T`.
+pub fn gcd<T: Integer>(a: T, b: T) -> T {
+    if a == T::MIN || b == T::MIN {
+        // `T::MIN.abs()` overflows for signed types, so work on the unsigned magnitudes instead
+        let (mut a, mut b) = (a.to_i128().unsigned_abs(), b.to_i128().unsigned_abs());
+        while b != 0 {
+            (a, b) = (b, a % b);
+        }
+        return i128::try_from(a)
+            .ok()
+            .and_then(T::from_i128)
+            .expect("gcd overflowed");
+    }
+
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != T::ZERO {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Least common multiple, always non-negative. Panics if it doesn't fit `T`.
+pub fn lcm<T: Integer>(a: T, b: T) -> T {
+    if a == T::ZERO || b == T::ZERO {
+        return T::ZERO;
+    }
+    (a / gcd(a, b) * b).abs()
+}
+
+/// Extended Euclidean algorithm. Returns `(g, x, y)` with `a * x + b * y == g`.
+pub fn extended_gcd<T: Signed>(a: T, b: T) -> (T, T, T) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_s, mut s) = (T::ONE, T::ZERO);
+    let (mut old_t, mut t) = (T::ZERO, T::ONE);
+
+    while r != T::ZERO {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_s, s) = (s, old_s - q * s);
+        (old_t, t) = (t, old_t - q * t);
+    }
+
+    if old_r < T::ZERO {
+        (-old_r, -old_s, -old_t)
+    } else {
+        (old_r, old_s, old_t)
+    }
+}
+
+/// Modular multiplicative inverse of `a` modulo `m`, if `a` and `m` are coprime.
+pub fn mod_inverse<T: Integer>(a: T, m: T) -> Option<T> {
+    let m = m.to_i128();
+    let (g, x, _) = extended_gcd(a.to_i128().rem_euclid(m), m);
+    if g != 1 {
+        return None;
+    }
+    T::from_i128(x.rem_euclid(m))
+}
+
+/// `a * b mod m` for `a, b < m`, without overflowing for any modulus.
+fn mul_mod(a: u128, b: u128, m: u128) -> u128 {
+    if let Some(product) = a.checked_mul(b) {
+        return product % m;
+    }
+    // double-and-add, every intermediate value stays below 2 * m
+    let (mut a, mut b, mut result) = (a, b, 0u128);
+    while b > 0 {
+        if b & 1 == 1 {
+            result = add_mod(result, a, m);
+        }
+        a = add_mod(a, a, m);
+        b >>= 1;
+    }
+    result
+}
+
+fn add_mod(a: u128, b: u128, m: u128) -> u128 {
+    if a >= m - b {
+        a - (m - b)
+    } else {
+        a + b
+    }
+}
+
+/// Computes `base^exp mod modulus` by repeated squaring. The result is in `0..modulus`.
+pub fn mod_pow<T: Integer>(base: T, exp: T, modulus: T) -> T {
+    let m = modulus.to_i128();
+    assert!(m > 0, "modulus must be positive");
+    assert!(exp >= T::ZERO, "exponent must be non-negative");
+
+    let m = m as u128;
+    let mut base = base.to_i128().rem_euclid(m as i128) as u128;
+    let mut exp = exp.to_i128() as u128;
+    let mut result = 1 % m;
+
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod(result, base, m);
+        }
+        base = mul_mod(base, base, m);
+        exp >>= 1;
+    }
+
+    T::from_i128(result as i128).unwrap()
+}
+
+/// Why [`crt`] found no representable answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrtError {
+    /// The congruences contradict each other.
+    NoSolution,
+    /// A solution exists, but the combined modulus does not fit the integer type.
+    Overflow,
+}
+
+impl Display for CrtError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CrtError::NoSolution => write!(f, "the congruences have no common solution"),
+            CrtError::Overflow => write!(f, "the combined modulus overflows"),
+        }
+    }
+}
+
+impl std::error::Error for CrtError {}
+
+/// Chinese remainder theorem over `(residue, modulus)` pairs. Moduli do not need to be coprime.
+/// Returns `(x, lcm)` so that every `x + k * lcm` satisfies all congruences.
+///
+/// Fails with [`CrtError::NoSolution`] if the congruences contradict each other, and with
+/// [`CrtError::Overflow`] if they don't but the lcm of the moduli does not fit in `T`.
+pub fn crt<T: Integer>(congruences: &[(T, T)]) -> Result<(T, T), CrtError> {
+    let mut x: i128 = 0;
+    let mut m: i128 = 1;
+
+    for (i, &(residue, modulus)) in congruences.iter().enumerate() {
+        let (a, n) = (residue.to_i128(), modulus.to_i128());
+        let (g, p, _) = extended_gcd(m, n);
+        if (a - x) % g != 0 {
+            return Err(CrtError::NoSolution);
+        }
+        let n_g = n / g;
+        let Some(next_m) = m.checked_mul(n_g) else {
+            // pairwise agreement modulo the gcd is enough for a solution to exist
+            return if compatible(congruences, i) {
+                Err(CrtError::Overflow)
+            } else {
+                Err(CrtError::NoSolution)
+            };
+        };
+        let diff = ((a - x) / g).rem_euclid(n_g) as u128;
+        let t = mul_mod(diff, p.rem_euclid(n_g) as u128, n_g as u128) as i128;
+        x = (x + mul_mod(m as u128, t as u128, next_m as u128) as i128).rem_euclid(next_m);
+        m = next_m;
+    }
+
+    match (T::from_i128(x), T::from_i128(m)) {
+        (Some(x), Some(m)) => Ok((x, m)),
+        _ => Err(CrtError::Overflow),
+    }
+}
+
+/// Whether every pair of congruences, with at least one of them from `from` on, agrees modulo the
+/// gcd of their moduli. The ones before `from` are already known to agree.
+fn compatible<T: Integer>(congruences: &[(T, T)], from: usize) -> bool {
+    (from..congruences.len()).all(|j| {
+        let (a, n) = (congruences[j].0.to_i128(), congruences[j].1.to_i128());
+        congruences[..j].iter().all(|&(b, k)| {
+            let g = gcd(n, k.to_i128());
+            (a - b.to_i128()) % g == 0
+        })
+    })
+}
+
+/// Appends the decimal digits of `b` to `a`, e.g. `concat(12, 345) == 12345`.
+/// The result is negative if `a` is, e.g. `concat(-12, 3) == -123`; `b` only contributes its digits
+/// (unless `a` is `0`, where `concat(0, -3) == -3`).
+/// Panics on overflow, use [`checked_concat`] to handle it.
+pub fn concat<T: Integer>(a: T, b: T) -> T {
+    checked_concat(a, b).expect("concatenation overflowed")
+}
+
+pub fn checked_concat<T: Integer>(a: T, b: T) -> Option<T> {
+    let head = a.checked_mul(T::TEN.checked_pow(b.digit_count())?)?;
+    let negative = a < T::ZERO || (a == T::ZERO && b < T::ZERO);
+    // add |b| away from zero, in the direction of the result's sign
+    if negative == (b < T::ZERO) {
+        head.checked_add(b)
+    } else {
+        head.checked_sub(b)
+    }
+}
+
+fn same_magnitude<T: Integer>(a: T, b: T) -> bool {
+    a.to_i128().unsigned_abs() == b.to_i128().unsigned_abs()
+}
+
+/// Checks whether the decimal digits of `n` end with those of `suffix`, e.g. `ends_with(12345, 45)`.
+/// Signs are ignored, so `ends_with(-123, 23)` holds.
+pub fn ends_with<T: Integer>(n: T, suffix: T) -> bool {
+    match T::TEN.checked_pow(suffix.digit_count()) {
+        Some(pow) => same_magnitude(n % pow, suffix),
+        None => same_magnitude(n, suffix),
+    }
+}
+
+/// Removes the decimal digits of `suffix` from `n`, e.g. `strip_suffix(12345, 45) == Some(123)`.
+/// The inverse of [`concat`]: the sign of `n` is kept, the sign of `suffix` is ignored.
+pub fn strip_suffix<T: Integer>(n: T, suffix: T) -> Option<T> {
+    match T::TEN.checked_pow(suffix.digit_count()) {
+        Some(pow) if same_magnitude(n % pow, suffix) => Some(n / pow),
+        None if same_magnitude(n, suffix) => Some(T::ZERO),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcd_and_lcm() {
+        assert_eq!(gcd(48u32, 18), 6);
+        assert_eq!(gcd(-48i64, 18), 6);
+        assert_eq!(lcm(4u64, 6), 12);
+        assert_eq!(lcm(-4i32, 6), 12);
+        assert_eq!(extended_gcd(240i64, 46), (2, -9, 47));
+
+        assert_eq!(gcd(i64::MIN, 2), 2);
+        assert_eq!(gcd(6, i8::MIN), 2);
+        assert_eq!(gcd(i128::MIN, i128::MIN / 4), 1 << 125);
+        assert_eq!(gcd(0u8, 12), 12);
+        assert_eq!(lcm(i64::MIN / 2, 4), 1 << 62);
+    }
+
+    #[test]
+    fn modular_arithmetic() {
+        assert_eq!(mod_inverse(3u64, 11), Some(4));
+        assert_eq!(mod_inverse(-3i64, 11), Some(7));
+        assert_eq!(mod_inverse(4u64, 8), None);
+        assert_eq!(mod_pow(4u64, 13, 497), 445);
+        assert_eq!(mod_pow(u64::MAX - 1, u64::MAX, u64::MAX), u64::MAX - 1);
+        // 2^127 = 1 (mod 2^127 - 1), products of residues this size need more than 128 bits
+        assert_eq!(mod_pow(2i128, 127 * 3 + 5, i128::MAX), 32);
+        assert_eq!(mod_pow(2i128, 127, i128::MAX), 1);
+        assert_eq!(crt(&[(2u64, 3), (3, 5), (2, 7)]), Ok((23, 105)));
+        assert_eq!(crt(&[(1i64, 4), (3, 6)]), Ok((9, 12)));
+        assert_eq!(crt(&[(1i64, 4), (2, 6)]), Err(CrtError::NoSolution));
+        assert_eq!(crt(&[(1u8, 16), (2, 17)]), Err(CrtError::Overflow));
+        assert_eq!(
+            crt(&[(1u8, 16), (2, 17), (0, 2)]),
+            Err(CrtError::NoSolution)
+        );
+    }
+
+    #[test]
+    fn digit_operations() {
+        assert_eq!(0u8.digit_count(), 1);
+        assert_eq!((-12345i64).digit_count(), 5);
+        assert_eq!(concat(12u64, 345), 12345);
+        assert_eq!(concat(12u64, 0), 120);
+        assert_eq!(checked_concat(200u8, 1), None);
+        assert!(ends_with(12345u64, 45));
+        assert!(!ends_with(12345u64, 55));
+        assert!(!ends_with(5u8, 105));
+        assert_eq!(strip_suffix(12345u64, 45), Some(123));
+        assert_eq!(strip_suffix(45u64, 45), Some(0));
+        assert_eq!(strip_suffix(200u8, 200), Some(0));
+
+        assert_eq!(concat(-12i64, 3), -123);
+        assert_eq!(concat(12i64, -3), 123);
+        assert_eq!(concat(0i64, -3), -3);
+        assert_eq!(checked_concat(-12i8, 9), None);
+        assert!(ends_with(-123i64, 23));
+        assert!(ends_with(-123i64, -23));
+        assert!(!ends_with(-123i64, 13));
+        assert_eq!(strip_suffix(-123i64, 23), Some(-1));
+        assert_eq!(strip_suffix(i8::MIN, i8::MIN), Some(0));
+    }
+}
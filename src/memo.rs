@@ -0,0 +1,141 @@
+use std::{
+    hash::Hash,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
+use crate::hashing::FastHashMap;
+
+// Totals across all `Memo` instances, reported by the runner in debug builds.
+static TOTAL_HITS: AtomicU64 = AtomicU64::new(0);
+static TOTAL_MISSES: AtomicU64 = AtomicU64::new(0);
+
+/// A cache for recursive functions. Keys can borrow from the input (e.g. `&str`),
+/// so nothing needs to be cloned per lookup.
+///
+/// ```ignore
+/// fn arrangements<'a>(memo: &mut Memo<&'a str, u64>, design: &'a str, towels: &[&str]) -> u64 {
+///     memo.get_or_compute(design, |memo| {
+///         if design.is_empty() {
+///             return 1;
+///         }
+///         towels
+///             .iter()
+///             .filter_map(|t| design.strip_prefix(t))
+///             .map(|rest| arrangements(memo, rest, towels))
+///             .sum()
+///     })
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Memo<K, V> {
+    cache: FastHashMap<K, V>,
+    hits: u64,
+    misses: u64,
+}
+
+impl<K: Hash + Eq, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Self {
+            cache: FastHashMap::default(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Returns the cached value for `key`, or computes and caches it.
+    /// `compute` receives the memo again so it can recurse.
+    pub fn get_or_compute(&mut self, key: K, compute: impl FnOnce(&mut Self) -> V) -> V {
+        if let Some(val) = self.cache.get(&key) {
+            self.hits += 1;
+            #[cfg(debug_assertions)]
+            TOTAL_HITS.fetch_add(1, Ordering::Relaxed);
+            return val.clone();
+        }
+
+        self.misses += 1;
+        #[cfg(debug_assertions)]
+        TOTAL_MISSES.fetch_add(1, Ordering::Relaxed);
+
+        let val = compute(self);
+        self.cache.insert(key, val.clone());
+        val
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.cache.get(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    /// Empties the cache and resets the statistics.
+    pub fn clear(&mut self) {
+        self.cache.clear();
+        self.hits = 0;
+        self.misses = 0;
+    }
+}
+
+impl<K: Hash + Eq, V: Clone> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Returns `(hits, misses)` summed over every `Memo` since the last [`reset_stats`].
+/// Only tracked in debug builds, always `(0, 0)` in release.
+pub fn stats() -> (u64, u64) {
+    (
+        TOTAL_HITS.load(Ordering::Relaxed),
+        TOTAL_MISSES.load(Ordering::Relaxed),
+    )
+}
+
+pub fn reset_stats() {
+    TOTAL_HITS.store(0, Ordering::Relaxed);
+    TOTAL_MISSES.store(0, Ordering::Relaxed);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arrangements<'a>(memo: &mut Memo<&'a str, u64>, design: &'a str, towels: &[&str]) -> u64 {
+        memo.get_or_compute(design, |memo| {
+            if design.is_empty() {
+                return 1;
+            }
+            towels
+                .iter()
+                .filter_map(|t| design.strip_prefix(t))
+                .map(|rest| arrangements(memo, rest, towels))
+                .sum()
+        })
+    }
+
+    #[test]
+    fn memoizes_borrowed_keys() {
+        let towels = ["r", "wr", "b", "g", "bwu", "rb", "gb", "br"];
+        let mut memo = Memo::new();
+        assert_eq!(arrangements(&mut memo, "rrbgbr", &towels), 6);
+        assert_eq!(memo.misses(), memo.len() as u64);
+        assert!(memo.hits() > 0);
+
+        let misses = memo.misses();
+        assert_eq!(arrangements(&mut memo, "rrbgbr", &towels), 6);
+        assert_eq!(memo.misses(), misses);
+    }
+}
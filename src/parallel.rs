@@ -0,0 +1,94 @@
+//! Helpers to fan brute-force work out over all cores.
+//!
+//! Work is only split across threads when the `parallel` feature is enabled.
+//! Without it, every helper runs sequentially on the calling thread, so solutions
+//! compile and behave the same either way.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// Most threads used by any helper since the last reset, reported by the runner.
+static THREADS_USED: AtomicUsize = AtomicUsize::new(1);
+
+/// Number of worker threads the helpers will use.
+pub fn threads() -> usize {
+    if cfg!(feature = "parallel") {
+        std::thread::available_parallelism().map_or(1, |n| n.get())
+    } else {
+        1
+    }
+}
+
+/// Most threads used by any helper since the last [`reset_threads_used`].
+pub fn threads_used() -> usize {
+    THREADS_USED.load(Ordering::Relaxed)
+}
+
+pub fn reset_threads_used() {
+    THREADS_USED.store(1, Ordering::Relaxed);
+}
+
+/// Applies `f` to every item on a scoped pool of threads, keeping the input order.
+pub fn par_map<T: Send, U: Send>(
+    items: impl IntoIterator<Item = T>,
+    f: impl Fn(T) -> U + Sync,
+) -> Vec<U> {
+    let mut items = items.into_iter().collect::<Vec<_>>();
+    let workers = threads().min(items.len());
+
+    if workers <= 1 {
+        return items.into_iter().map(f).collect();
+    }
+
+    THREADS_USED.fetch_max(workers, Ordering::Relaxed);
+
+    let chunk_size = items.len().div_ceil(workers);
+    let mut chunks = vec![];
+    while items.len() > chunk_size {
+        let tail = items.split_off(items.len() - chunk_size);
+        chunks.push(tail);
+    }
+    chunks.push(items);
+    chunks.reverse();
+
+    let f = &f;
+    std::thread::scope(|scope| {
+        let handles = chunks
+            .into_iter()
+            .map(|chunk| scope.spawn(move || chunk.into_iter().map(f).collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+
+        handles
+            .into_iter()
+            .flat_map(|h| h.join().unwrap())
+            .collect()
+    })
+}
+
+/// Counts the items matching `predicate`, evaluated on a scoped pool of threads.
+pub fn par_filter_count<T: Send>(
+    items: impl IntoIterator<Item = T>,
+    predicate: impl Fn(T) -> bool + Sync,
+) -> usize {
+    par_map(items, predicate).into_iter().filter(|&b| b).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_in_order() {
+        let items = (0..1000u64).collect::<Vec<_>>();
+        assert_eq!(
+            par_map(&items, |n| n * 2),
+            items.iter().map(|n| n * 2).collect::<Vec<_>>()
+        );
+        assert_eq!(par_map(Vec::<u64>::new(), |n| n), vec![]);
+    }
+
+    #[test]
+    fn counts_matches() {
+        assert_eq!(par_filter_count(0..1000, |n| n % 3 == 0), 334);
+        assert_eq!(par_filter_count(["a", "bb", "c"], |s| s.len() == 1), 2);
+    }
+}
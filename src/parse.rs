@@ -0,0 +1,338 @@
+use std::{any::type_name, fmt::Display, str::FromStr};
+
+/// Error for input that doesn't have the expected shape, located in the input.
+///
+/// Its `Display` output points at the offending text with a caret:
+///
+/// ```text
+/// line 3, column 4: expected u32, found `x`
+///   |
+/// 3 | 47|x
+///   |    ^
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line number, if known.
+    pub line: Option<usize>,
+    /// 1-based column (in chars) of the offending text within `source_line`.
+    pub column: usize,
+    /// The full line the error occurred in.
+    pub source_line: String,
+    /// The text that could not be parsed.
+    pub text: String,
+    /// What was expected instead, e.g. a type name or a separator.
+    pub expected: String,
+}
+
+impl ParseError {
+    /// Creates an error for `text`, which must be a subslice of `source` so its position can be determined.
+    /// If `source` spans several lines, the line number is taken relative to it.
+    pub fn new(source: &str, text: &str, expected: impl Into<String>) -> Self {
+        let offset = subslice_offset(source, text);
+        debug_assert!(offset.is_some(), "`{text}` is not a subslice of `{source}`");
+        let offset = offset.unwrap_or(0);
+        let line_start = source[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = source[offset..]
+            .find('\n')
+            .map_or(source.len(), |i| offset + i);
+
+        Self {
+            line: source
+                .contains('\n')
+                .then(|| source[..offset].matches('\n').count() + 1),
+            column: source[line_start..offset].chars().count() + 1,
+            source_line: source[line_start..line_end]
+                .trim_end_matches('\r')
+                .to_string(),
+            text: text.to_string(),
+            expected: expected.into(),
+        }
+    }
+
+    pub fn with_line(mut self, line: usize) -> Self {
+        self.line = Some(line);
+        self
+    }
+}
+
+fn subslice_offset(source: &str, text: &str) -> Option<usize> {
+    let start = (text.as_ptr() as usize).checked_sub(source.as_ptr() as usize)?;
+    (start + text.len() <= source.len()).then_some(start)
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let gutter = self.line.map(|l| l.to_string()).unwrap_or_default();
+        let width = gutter.len();
+        let remaining = self.source_line.chars().count() + 1 - self.column;
+        let carets = self.text.chars().count().clamp(1, remaining.max(1));
+
+        match self.line {
+            Some(line) => write!(f, "line {line}, column {}: ", self.column)?,
+            None => write!(f, "column {}: ", self.column)?,
+        }
+        writeln!(f, "expected {}, found `{}`", self.expected, self.text)?;
+        writeln!(f, "{:width$} |", "")?;
+        writeln!(f, "{gutter:>width$} | {}", self.source_line)?;
+        write!(
+            f,
+            "{:width$} | {}{}",
+            "",
+            " ".repeat(self.column - 1),
+            "^".repeat(carets)
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses a single value, reporting the target type on failure.
+pub fn parse_field<T: FromStr>(s: &str) -> Result<T, ParseError> {
+    parse_field_in(s, s)
+}
+
+/// Parses `field`, a subslice of `source`, so that errors point at its position in `source`.
+pub fn parse_field_in<T: FromStr>(source: &str, field: &str) -> Result<T, ParseError> {
+    field
+        .trim()
+        .parse()
+        .map_err(|_| ParseError::new(source, field, type_name::<T>()))
+}
+
+/// Parses every non-empty line with `f`, adding line numbers to its errors.
+pub fn parse_lines<T>(
+    input: &str,
+    f: impl Fn(&str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, l)| !l.is_empty())
+        .map(|(i, l)| f(l).map_err(|e| e.with_line(i + 1)))
+        .collect()
+}
+
+/// Like [`parse_lines`] for `section`, a subslice of `source`, numbering lines from the start of `source`.
+pub fn parse_lines_in<T>(
+    source: &str,
+    section: &str,
+    f: impl Fn(&str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    parse_lines(section, f).map_err(|mut err| {
+        let offset = subslice_offset(source, section);
+        debug_assert!(
+            offset.is_some(),
+            "`{section}` is not a subslice of `{source}`"
+        );
+        let preceding = source[..offset.unwrap_or(0)].matches('\n').count();
+        err.line = err.line.map(|line| line + preceding);
+        err
+    })
+}
+
+/// Extracts every integer in `line`, treating a `-` directly before digits as a sign.
+/// `"x=-3, y=12"` yields `[-3, 12]`.
+pub fn ints<T: FromStr>(line: &str) -> Result<Vec<T>, ParseError> {
+    number_spans(line, true)
+        .map(|(start, end)| parse_field_in(line, &line[start..end]))
+        .collect()
+}
+
+/// Extracts every run of digits in `line`, ignoring any signs. `"1-3"` yields `[1, 3]`.
+pub fn uints<T: FromStr>(line: &str) -> Result<Vec<T>, ParseError> {
+    number_spans(line, false)
+        .map(|(start, end)| parse_field_in(line, &line[start..end]))
+        .collect()
+}
+
+fn number_spans(line: &str, signed: bool) -> impl Iterator<Item = (usize, usize)> + '_ {
+    let bytes = line.as_bytes();
+    let mut i = 0;
+    std::iter::from_fn(move || {
+        while i < bytes.len() && !bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+        if i == bytes.len() {
+            return None;
+        }
+        let mut start = i;
+        if signed && start > 0 && bytes[start - 1] == b'-' {
+            start -= 1;
+        }
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+        Some((start, i))
+    })
+}
+
+/// Splits input into blank-line-separated sections.
+pub fn sections(input: &str) -> Vec<&str> {
+    input
+        .split("\n\n")
+        .map(|s| s.trim_matches('\n'))
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+/// Parses a `key: values` line, with values separated by whitespace or commas.
+/// `"190: 10 19"` yields `(190, [10, 19])`.
+pub fn key_values<K: FromStr, V: FromStr>(line: &str) -> Result<(K, Vec<V>), ParseError> {
+    let (key, values) = line
+        .split_once(':')
+        .ok_or_else(|| ParseError::new(line, line, "`key: values`"))?;
+    let values = values
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter(|v| !v.is_empty())
+        .map(|v| parse_field_in(line, v))
+        .collect::<Result<_, _>>()?;
+    Ok((parse_field_in(line, key)?, values))
+}
+
+/// Parses `N` whitespace-separated columns into parallel vectors.
+pub fn columns<T: FromStr, const N: usize>(input: &str) -> Result<[Vec<T>; N], ParseError> {
+    let mut columns: [Vec<T>; N] = std::array::from_fn(|_| vec![]);
+    for (i, line) in input.lines().enumerate().filter(|(_, l)| !l.is_empty()) {
+        let fields = line.split_whitespace().collect::<Vec<_>>();
+        if fields.len() != N {
+            return Err(ParseError::new(line, line, format!("{N} columns")).with_line(i + 1));
+        }
+        for (column, field) in columns.iter_mut().zip(fields) {
+            column.push(parse_field_in(line, field).map_err(|e| e.with_line(i + 1))?);
+        }
+    }
+    Ok(columns)
+}
+
+/// Splits `input` according to a format with `{}` placeholders, returning the placeholder texts.
+/// Used by [`scan!`](crate::scan).
+pub fn scan_fields<'a>(input: &'a str, format: &str) -> Result<Vec<&'a str>, ParseError> {
+    let mut literals = format.split("{}");
+    let prefix = literals.next().unwrap_or_default();
+    let expected = || format!("`{format}`");
+    let mut rest = input
+        .strip_prefix(prefix)
+        .ok_or_else(|| ParseError::new(input, input, expected()))?;
+
+    let mut fields = vec![];
+    let mut literals = literals.peekable();
+    while let Some(literal) = literals.next() {
+        if literal.is_empty() {
+            if literals.peek().is_some() {
+                return Err(ParseError::new(
+                    format,
+                    format,
+                    "literal text between placeholders",
+                ));
+            }
+            fields.push(rest);
+            rest = "";
+            continue;
+        }
+        let (field, tail) = rest
+            .split_once(literal)
+            .ok_or_else(|| ParseError::new(input, rest, expected()))?;
+        fields.push(field);
+        rest = tail;
+    }
+
+    if !rest.is_empty() {
+        return Err(ParseError::new(input, rest, expected()));
+    }
+
+    Ok(fields)
+}
+
+/// Parses a line with a fixed format into a tuple of typed values.
+///
+/// ```ignore
+/// let (before, after) = scan!("47|53", "{}|{}", u32, u32)?;
+/// ```
+#[macro_export]
+macro_rules! scan {
+    ($input:expr, $format:expr, $($t:ty),+ $(,)?) => {{
+        let (input, format): (&str, &str) = ($input, $format);
+        $crate::parse::scan_fields(input, format).and_then(|fields| {
+            let mut fields = fields.into_iter();
+            Ok(($(
+                $crate::parse::parse_field_in::<$t>(input, fields.next().ok_or_else(|| {
+                    $crate::parse::ParseError::new(input, input, format!("`{format}`"))
+                })?)?,
+            )+))
+        })
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_integers() {
+        assert_eq!(ints::<i64>("p=0,4 v=-3,12"), Ok(vec![0, 4, -3, 12]));
+        assert_eq!(uints::<u32>("1-3 a: 17"), Ok(vec![1, 3, 17]));
+        assert!(ints::<u32>("x=-3").is_err());
+        assert!(ints::<u8>("300").is_err());
+    }
+
+    #[test]
+    fn splits_structures() {
+        assert_eq!(sections("a\nb\n\nc\n"), vec!["a\nb", "c"]);
+        assert_eq!(
+            key_values::<u64, u64>("3267: 81 40 27"),
+            Ok((3267, vec![81, 40, 27]))
+        );
+        assert_eq!(
+            columns::<u32, 2>("3   4\n4   3\n"),
+            Ok([vec![3, 4], vec![4, 3]])
+        );
+        let err = columns::<u32, 2>("3   4\n5   x").unwrap_err();
+        assert_eq!((err.line, err.column), (Some(2), 5));
+    }
+
+    #[test]
+    fn locates_errors() {
+        let err =
+            parse_lines("1|2\n\n47|x5\n", |l| crate::scan!(l, "{}|{}", u32, u32)).unwrap_err();
+        assert_eq!(err.line, Some(3));
+        assert_eq!(err.column, 4);
+        assert_eq!(err.text, "x5");
+        assert_eq!(
+            err.to_string(),
+            "line 3, column 4: expected u32, found `x5`\n  |\n3 | 47|x5\n  |    ^^"
+        );
+
+        let err = crate::Matrix::try_parse("12\n3x", |c| c.to_digit(10), "a digit").unwrap_err();
+        assert_eq!((err.line, err.column, err.text.as_str()), (Some(2), 2, "x"));
+
+        let err = ParseError::new("ab\ncd", &"ab\ncd"[4..], "digit");
+        assert_eq!(
+            (err.line, err.column, err.source_line.as_str()),
+            (Some(2), 2, "cd")
+        );
+
+        let input = "\n1|2\n\n47|x5\n";
+        let err = parse_lines_in(input, sections(input)[1], |l| {
+            crate::scan!(l, "{}|{}", u32, u32)
+        })
+        .unwrap_err();
+        assert_eq!((err.line, err.column), (Some(4), 4));
+    }
+
+    #[test]
+    fn scans_fixed_formats() {
+        assert_eq!(crate::scan!("47|53", "{}|{}", u32, u32), Ok((47, 53)));
+        assert_eq!(
+            crate::scan!(
+                "Button A: X+94, Y+34",
+                "Button {}: X+{}, Y+{}",
+                char,
+                i64,
+                i64
+            ),
+            Ok(('A', 94, 34))
+        );
+        assert!(crate::scan!("47-53", "{}|{}", u32, u32).is_err());
+        assert!(crate::scan!("47|x", "{}|{}", u32, u32).is_err());
+    }
+}
@@ -0,0 +1,151 @@
+use std::ops::{Bound, RangeBounds};
+
+use crate::Matrix;
+
+/// Resolves `range` to `start..end`, clamped to `0..len`.
+fn clamp(range: &impl RangeBounds<usize>, len: usize) -> (usize, usize) {
+    let start = match range.start_bound() {
+        Bound::Included(&s) => s,
+        Bound::Excluded(&s) => s.saturating_add(1),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&e) => e.saturating_add(1),
+        Bound::Excluded(&e) => e,
+        Bound::Unbounded => len,
+    };
+    (start.min(len), end.min(len).max(start.min(len)))
+}
+
+/// Running totals of a sequence, answering sums over any index range in O(1).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrefixSums {
+    sums: Vec<i64>,
+}
+
+impl PrefixSums {
+    pub fn new(values: impl IntoIterator<Item = i64>) -> Self {
+        let mut sums = vec![0];
+        for v in values {
+            sums.push(sums[sums.len() - 1] + v);
+        }
+        Self { sums }
+    }
+
+    /// One table per row of `matrix`, each cell converted with `f`.
+    pub fn rows<T>(matrix: &Matrix<T>, f: impl Fn(&T) -> i64) -> Vec<Self> {
+        matrix
+            .cells
+            .iter()
+            .map(|row| Self::new(row.iter().map(&f)))
+            .collect()
+    }
+
+    /// One table per column of `matrix`, each cell converted with `f`.
+    pub fn columns<T>(matrix: &Matrix<T>, f: impl Fn(&T) -> i64) -> Vec<Self> {
+        (0..matrix.width)
+            .map(|x| Self::new(matrix.cells.iter().map(|row| f(&row[x]))))
+            .collect()
+    }
+
+    /// Number of values in the sequence.
+    pub fn len(&self) -> usize {
+        self.sums.len() - 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Sum of the values in `range`. Parts of the range past the end are ignored.
+    pub fn sum(&self, range: impl RangeBounds<usize>) -> i64 {
+        let (start, end) = clamp(&range, self.len());
+        self.sums[end] - self.sums[start]
+    }
+}
+
+/// A summed-area table: the 2D counterpart of [`PrefixSums`], answering sums over any
+/// rectangle of a [`Matrix`] in O(1).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SummedArea {
+    table: Vec<i64>,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl SummedArea {
+    pub fn new<T>(matrix: &Matrix<T>, f: impl Fn(&T) -> i64) -> Self {
+        let stride = matrix.width + 1;
+        let mut table = vec![0; stride * (matrix.height + 1)];
+        for (y, row) in matrix.cells.iter().enumerate() {
+            for (x, val) in row.iter().enumerate() {
+                table[(y + 1) * stride + x + 1] =
+                    f(val) + table[y * stride + x + 1] + table[(y + 1) * stride + x]
+                        - table[y * stride + x];
+            }
+        }
+        Self {
+            table,
+            width: matrix.width,
+            height: matrix.height,
+        }
+    }
+
+    /// Counts cells matching `predicate`, e.g. `|c| *c == '#'` for obstacles.
+    pub fn count<T>(matrix: &Matrix<T>, predicate: impl Fn(&T) -> bool) -> Self {
+        Self::new(matrix, |val| predicate(val).into())
+    }
+
+    fn at(&self, x: usize, y: usize) -> i64 {
+        self.table[y * (self.width + 1) + x]
+    }
+
+    /// Sum over the rectangle spanned by the column range `xs` and the row range `ys`.
+    /// Parts of the rectangle outside the grid are ignored.
+    pub fn sum(&self, xs: impl RangeBounds<usize>, ys: impl RangeBounds<usize>) -> i64 {
+        let (x0, x1) = clamp(&xs, self.width);
+        let (y0, y1) = clamp(&ys, self.height);
+        self.at(x1, y1) - self.at(x0, y1) - self.at(x1, y0) + self.at(x0, y0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sums_ranges() {
+        let sums = PrefixSums::new([3, 1, 4, 1, 5, 9, 2, 6]);
+        assert_eq!(sums.sum(..), 31);
+        assert_eq!(sums.sum(2..5), 10);
+        assert_eq!(sums.sum(5..=5), 9);
+        assert_eq!(sums.sum(6..100), 8);
+        assert_eq!(sums.sum(20..), 0);
+        assert_eq!(sums.sum(..=usize::MAX), 31);
+        assert_eq!(sums.sum((Bound::Excluded(usize::MAX), Bound::Unbounded)), 0);
+
+        // best window of 3
+        let best = (0..=sums.len() - 3).map(|i| sums.sum(i..i + 3)).max();
+        assert_eq!(best, Some(17));
+    }
+
+    #[test]
+    fn sums_rectangles() {
+        let matrix = Matrix::<char>::try_from("#..#\n.##.\n#..#\n").unwrap();
+        let obstacles = SummedArea::count(&matrix, |c| *c == '#');
+        assert_eq!(obstacles.sum(.., ..), 6);
+        assert_eq!(obstacles.sum(1..=2, 0..2), 2);
+        assert_eq!(obstacles.sum(3.., ..), 2);
+        assert_eq!(obstacles.sum(0..1, 1..=1), 0);
+
+        let digits = Matrix::<u32>::try_from("123\n456\n").unwrap();
+        let area = SummedArea::new(&digits, |d| i64::from(*d));
+        assert_eq!(area.sum(1.., ..), 2 + 3 + 5 + 6);
+
+        let rows = PrefixSums::rows(&matrix, |c| (*c == '#').into());
+        let columns = PrefixSums::columns(&matrix, |c| (*c == '#').into());
+        assert_eq!(rows[1].sum(..2), 1);
+        assert_eq!(columns[0].sum(..), 2);
+        assert_eq!(columns.len(), 4);
+    }
+}
@@ -0,0 +1,315 @@
+use std::{
+    fmt::Debug,
+    ops::{Bound, RangeBounds, RangeInclusive},
+};
+
+/// Integer types that can be stored in a [`RangeSet`].
+///
+/// All arithmetic on range bounds goes through `i128`, so `u64` and `i64` values
+/// near their limits never overflow.
+pub trait RangeValue: Copy + Ord + Debug {
+    const MIN: Self;
+    const MAX: Self;
+
+    fn to_i128(self) -> i128;
+
+    fn from_i128(n: i128) -> Option<Self>;
+}
+
+macro_rules! impl_range_value {
+    ($($t:ty),*) => {
+        $(
+            impl RangeValue for $t {
+                const MIN: Self = <$t>::MIN;
+                const MAX: Self = <$t>::MAX;
+
+                fn to_i128(self) -> i128 {
+                    self as i128
+                }
+
+                fn from_i128(n: i128) -> Option<Self> {
+                    <$t>::try_from(n).ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_range_value!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+/// Converts any range expression (`a..b`, `a..=b`, `a..`, ...) to inclusive bounds.
+/// Returns `None` if the range is empty.
+fn to_inclusive<T: RangeValue>(range: impl RangeBounds<T>) -> Option<(T, T)> {
+    let start = match range.start_bound() {
+        Bound::Included(s) => *s,
+        Bound::Excluded(s) => T::from_i128(s.to_i128() + 1)?,
+        Bound::Unbounded => T::MIN,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(e) => *e,
+        Bound::Excluded(e) => T::from_i128(e.to_i128() - 1)?,
+        Bound::Unbounded => T::MAX,
+    };
+    (start <= end).then_some((start, end))
+}
+
+/// A set of integers stored as sorted, disjoint and non-adjacent inclusive ranges.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RangeSet<T: RangeValue> {
+    ranges: Vec<(T, T)>,
+}
+
+impl<T: RangeValue> RangeSet<T> {
+    pub fn new() -> Self {
+        Self { ranges: vec![] }
+    }
+
+    /// Adds a range, merging it with any overlapping or touching ranges.
+    pub fn insert(&mut self, range: impl RangeBounds<T>) {
+        let Some((mut start, mut end)) = to_inclusive(range) else {
+            return;
+        };
+
+        // first range that could touch the new one, i.e. `r.end + 1 >= start`.
+        let from = self
+            .ranges
+            .partition_point(|&(_, e)| e.to_i128() + 1 < start.to_i128());
+        // first range that lies strictly after the new one.
+        let to = self
+            .ranges
+            .partition_point(|&(s, _)| s.to_i128() <= end.to_i128() + 1);
+
+        if from < to {
+            start = start.min(self.ranges[from].0);
+            end = end.max(self.ranges[to - 1].1);
+        }
+
+        self.ranges.splice(from..to, [(start, end)]);
+    }
+
+    /// Removes a range from the set, splitting existing ranges where needed.
+    pub fn remove(&mut self, range: impl RangeBounds<T>) {
+        let Some((start, end)) = to_inclusive(range) else {
+            return;
+        };
+
+        let from = self.ranges.partition_point(|&(_, e)| e < start);
+        let to = self.ranges.partition_point(|&(s, _)| s <= end);
+
+        if from >= to {
+            return;
+        }
+
+        let mut remainder = vec![];
+        let (first_start, _) = self.ranges[from];
+        let (_, last_end) = self.ranges[to - 1];
+
+        if first_start < start {
+            remainder.push((first_start, T::from_i128(start.to_i128() - 1).unwrap()));
+        }
+        if last_end > end {
+            remainder.push((T::from_i128(end.to_i128() + 1).unwrap(), last_end));
+        }
+
+        self.ranges.splice(from..to, remainder);
+    }
+
+    pub fn contains(&self, value: &T) -> bool {
+        let i = self.ranges.partition_point(|&(_, e)| e < *value);
+        self.ranges.get(i).is_some_and(|&(s, _)| s <= *value)
+    }
+
+    /// Returns `true` if every value of `range` is part of the set.
+    pub fn contains_range(&self, range: impl RangeBounds<T>) -> bool {
+        let Some((start, end)) = to_inclusive(range) else {
+            return true;
+        };
+        let i = self.ranges.partition_point(|&(_, e)| e < start);
+        self.ranges
+            .get(i)
+            .is_some_and(|&(s, e)| s <= start && end <= e)
+    }
+
+    /// Total count of integers in the set. This is a `u128` as the full `u64` / `i64` domain holds 2^64 values.
+    pub fn len(&self) -> u128 {
+        self.ranges
+            .iter()
+            .map(|&(s, e)| (e.to_i128() - s.to_i128() + 1) as u128)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.ranges.first().map(|&(s, _)| s)
+    }
+
+    pub fn max(&self) -> Option<T> {
+        self.ranges.last().map(|&(_, e)| e)
+    }
+
+    /// Iterates the merged ranges in ascending order.
+    pub fn iter(&self) -> impl Iterator<Item = RangeInclusive<T>> + '_ {
+        self.ranges.iter().map(|&(s, e)| s..=e)
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        for &(s, e) in &other.ranges {
+            result.insert(s..=e);
+        }
+        result
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut ranges = vec![];
+        let (mut i, mut j) = (0, 0);
+
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a_start, a_end) = self.ranges[i];
+            let (b_start, b_end) = other.ranges[j];
+            let start = a_start.max(b_start);
+            let end = a_end.min(b_end);
+            if start <= end {
+                ranges.push((start, end));
+            }
+            if a_end < b_end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        Self { ranges }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut result = self.clone();
+        for &(s, e) in &other.ranges {
+            result.remove(s..=e);
+        }
+        result
+    }
+}
+
+impl<T: RangeValue, R: RangeBounds<T>> FromIterator<R> for RangeSet<T> {
+    fn from_iter<I: IntoIterator<Item = R>>(iter: I) -> Self {
+        let mut set = Self::new();
+        for range in iter {
+            set.insert(range);
+        }
+        set
+    }
+}
+
+/// A piecewise offset table, e.g. a seed-to-soil map: every source range is shifted
+/// by its own offset and values outside of all source ranges map to themselves.
+#[derive(Debug, Clone, Default)]
+pub struct OffsetMap<T: RangeValue> {
+    entries: Vec<(T, T, i128)>,
+}
+
+impl<T: RangeValue> OffsetMap<T> {
+    pub fn new() -> Self {
+        Self { entries: vec![] }
+    }
+
+    /// Maps `source` onto a range of equal length starting at `destination`.
+    /// Source ranges are expected not to overlap.
+    pub fn insert(&mut self, source: impl RangeBounds<T>, destination: T) {
+        let Some((start, end)) = to_inclusive(source) else {
+            return;
+        };
+        let offset = destination.to_i128() - start.to_i128();
+        let i = self.entries.partition_point(|&(s, _, _)| s < start);
+        self.entries.insert(i, (start, end, offset));
+    }
+
+    /// Maps a single value. Panics if the offset moves it outside of `T`.
+    pub fn map(&self, value: T) -> T {
+        let i = self.entries.partition_point(|&(_, e, _)| e < value);
+        match self.entries.get(i) {
+            Some(&(s, _, offset)) if s <= value => {
+                T::from_i128(value.to_i128() + offset).expect("mapped value out of range")
+            }
+            _ => value,
+        }
+    }
+
+    /// Maps every value of a set, splitting ranges at the table boundaries.
+    pub fn map_set(&self, set: &RangeSet<T>) -> RangeSet<T> {
+        let mut result = RangeSet::new();
+        let mut unmapped = set.clone();
+
+        for &(s, e, offset) in &self.entries {
+            let piece = RangeSet::from_iter([s..=e]);
+            for range in set.intersection(&piece).iter() {
+                let start = T::from_i128(range.start().to_i128() + offset)
+                    .expect("mapped value out of range");
+                let end = T::from_i128(range.end().to_i128() + offset)
+                    .expect("mapped value out of range");
+                result.insert(start..=end);
+            }
+            unmapped.remove(s..=e);
+        }
+
+        result.union(&unmapped)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merges_overlapping_and_adjacent_ranges() {
+        let set = RangeSet::from_iter([1..5, 10..15, 5..7, 14..20]);
+        assert_eq!(set.iter().collect::<Vec<_>>(), vec![1..=6, 10..=19]);
+        assert_eq!(set.len(), 16);
+        assert!(set.contains(&6));
+        assert!(!set.contains(&7));
+    }
+
+    #[test]
+    fn set_operations() {
+        let a = RangeSet::from_iter([0..=10, 20..=30]);
+        let b = RangeSet::from_iter([5..=25]);
+        assert_eq!(a.union(&b).iter().collect::<Vec<_>>(), vec![0..=30],);
+        assert_eq!(
+            a.intersection(&b).iter().collect::<Vec<_>>(),
+            vec![5..=10, 20..=25]
+        );
+        assert_eq!(
+            a.difference(&b).iter().collect::<Vec<_>>(),
+            vec![0..=4, 26..=30]
+        );
+    }
+
+    #[test]
+    fn handles_extreme_bounds() {
+        let set = RangeSet::<u64>::from_iter([u64::MAX - 1..=u64::MAX, 0..=0]);
+        assert_eq!(set.len(), 3);
+        let full = RangeSet::<i64>::from_iter([..]);
+        assert_eq!(full.len(), 1 << 64);
+        let rest = full.difference(&RangeSet::from_iter([i64::MIN..=-1]));
+        assert_eq!(rest.min(), Some(0));
+        assert_eq!(rest.max(), Some(i64::MAX));
+    }
+
+    #[test]
+    fn maps_through_offset_table() {
+        // seed-to-soil map from 2023 day 5
+        let mut map = OffsetMap::<u64>::new();
+        map.insert(98..100, 50);
+        map.insert(50..98, 52);
+        assert_eq!(map.map(79), 81);
+        assert_eq!(map.map(14), 14);
+        assert_eq!(map.map(99), 51);
+
+        let seeds = RangeSet::from_iter([79..93, 55..68]);
+        let soil = map.map_set(&seeds);
+        assert_eq!(soil.iter().collect::<Vec<_>>(), vec![57..=69, 81..=94]);
+    }
+}
@@ -0,0 +1,320 @@
+use std::{any::type_name, marker::PhantomData};
+
+use crate::parse::ParseError;
+
+/// Integers that can be assembled digit by digit from ASCII bytes.
+pub trait ScanInt: Copy {
+    const ZERO: Self;
+    const SIGNED: bool;
+
+    /// Appends an ASCII digit, moving away from zero in the direction of the sign.
+    /// `None` if the result doesn't fit the type.
+    fn push_digit(self, digit: u8, negative: bool) -> Option<Self>;
+}
+
+macro_rules! impl_scan_int {
+    (unsigned: $($t:ty),*) => {
+        $( impl_scan_int!(@impl $t, false); )*
+    };
+    (signed: $($t:ty),*) => {
+        $( impl_scan_int!(@impl $t, true); )*
+    };
+    (@impl $t:ty, $signed:expr) => {
+        impl ScanInt for $t {
+            const ZERO: Self = 0;
+            const SIGNED: bool = $signed;
+
+            #[inline]
+            fn push_digit(self, digit: u8, negative: bool) -> Option<Self> {
+                let shifted = self.checked_mul(10)?;
+                let digit = (digit - b'0') as $t;
+                if negative {
+                    shifted.checked_sub(digit)
+                } else {
+                    shifted.checked_add(digit)
+                }
+            }
+        }
+    };
+}
+
+impl_scan_int!(unsigned: u8, u16, u32, u64, u128, usize);
+impl_scan_int!(signed: i8, i16, i32, i64, i128, isize);
+
+/// Iterator over all integers in a byte slice, skipping any non-digit bytes in between.
+///
+/// For signed types a `-` directly before the digits is read as a sign.
+/// For unsigned types it is treated like any other separator.
+///
+/// The iterator ends at the first number that doesn't fit `T`, see [`Numbers::overflowed`].
+#[derive(Debug, Clone)]
+pub struct Numbers<'a, T> {
+    bytes: &'a [u8],
+    pos: usize,
+    overflowed: bool,
+    _marker: PhantomData<T>,
+}
+
+impl<T> Numbers<'_, T> {
+    /// Whether scanning stopped early because a number didn't fit `T`.
+    pub fn overflowed(&self) -> bool {
+        self.overflowed
+    }
+}
+
+impl<T: ScanInt> Iterator for Numbers<'_, T> {
+    type Item = T;
+
+    #[inline]
+    fn next(&mut self) -> Option<T> {
+        if self.overflowed {
+            return None;
+        }
+        let bytes = self.bytes;
+        while self.pos < bytes.len() && !bytes[self.pos].is_ascii_digit() {
+            self.pos += 1;
+        }
+        if self.pos == bytes.len() {
+            return None;
+        }
+
+        let negative = T::SIGNED && self.pos > 0 && bytes[self.pos - 1] == b'-';
+        let mut n = T::ZERO;
+        while self.pos < bytes.len() && bytes[self.pos].is_ascii_digit() {
+            let Some(next) = n.push_digit(bytes[self.pos], negative) else {
+                self.overflowed = true;
+                return None;
+            };
+            n = next;
+            self.pos += 1;
+        }
+
+        Some(n)
+    }
+}
+
+/// Scans `bytes` for integers without allocating.
+///
+/// ```ignore
+/// let sum: u32 = numbers::<u32>(b"3   4\n4   3").sum();
+/// ```
+pub fn numbers<T: ScanInt>(bytes: &[u8]) -> Numbers<'_, T> {
+    Numbers {
+        bytes,
+        pos: 0,
+        overflowed: false,
+        _marker: PhantomData,
+    }
+}
+
+/// Strict iterator over the integers of a line, separated by runs of `separators`.
+///
+/// Unlike [`Numbers`], anything else in between is an error, as is a number that doesn't fit `T`.
+/// The error points into the line; the iterator ends after yielding it.
+#[derive(Debug, Clone)]
+pub struct Fields<'a, T> {
+    line: &'a str,
+    separators: &'a [u8],
+    pos: usize,
+    failed: bool,
+    _marker: PhantomData<T>,
+}
+
+impl<'a, T: ScanInt> Fields<'a, T> {
+    /// Byte offset in the line just past the last number read.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Reads exactly `N` numbers.
+    pub fn array<const N: usize>(mut self) -> Result<[T; N], ParseError> {
+        let mut out = [T::ZERO; N];
+        for slot in &mut out {
+            *slot = match self.next() {
+                Some(n) => n?,
+                None => {
+                    let rest = &self.line[self.line.len()..];
+                    return Err(ParseError::new(self.line, rest, format!("{N} numbers")));
+                }
+            };
+        }
+        let end = self.pos;
+        match self.next() {
+            Some(Err(e)) => Err(e),
+            Some(Ok(_)) => {
+                let extra = self.line[end..self.pos].trim_start_matches(|c: char| {
+                    c.is_ascii() && self.separators.contains(&(c as u8))
+                });
+                Err(ParseError::new(self.line, extra, format!("{N} numbers")))
+            }
+            None => Ok(out),
+        }
+    }
+
+    fn junk(&mut self) -> ParseError {
+        self.failed = true;
+        let len = self.line[self.pos..]
+            .chars()
+            .next()
+            .map_or(0, char::len_utf8);
+        let text = &self.line[self.pos..self.pos + len];
+        ParseError::new(
+            self.line,
+            text,
+            format!("{} or separator", type_name::<T>()),
+        )
+    }
+}
+
+impl<T: ScanInt> Iterator for Fields<'_, T> {
+    type Item = Result<T, ParseError>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+        let bytes = self.line.as_bytes();
+        while self.pos < bytes.len() && self.separators.contains(&bytes[self.pos]) {
+            self.pos += 1;
+        }
+        if self.pos == bytes.len() {
+            return None;
+        }
+
+        let start = self.pos;
+        let negative = T::SIGNED && bytes[start] == b'-';
+        let digits = start + usize::from(negative);
+        if digits == bytes.len() || !bytes[digits].is_ascii_digit() {
+            return Some(Err(self.junk()));
+        }
+        self.pos = digits;
+        let mut n = Some(T::ZERO);
+        while self.pos < bytes.len() && bytes[self.pos].is_ascii_digit() {
+            n = n.and_then(|n| n.push_digit(bytes[self.pos], negative));
+            self.pos += 1;
+        }
+        let Some(n) = n else {
+            self.failed = true;
+            let text = &self.line[start..self.pos];
+            return Some(Err(ParseError::new(self.line, text, type_name::<T>())));
+        };
+        if self.pos < bytes.len() && !self.separators.contains(&bytes[self.pos]) {
+            return Some(Err(self.junk()));
+        }
+
+        Some(Ok(n))
+    }
+}
+
+/// Scans `line` for integers separated by `separators`, reporting anything else as a [`ParseError`].
+///
+/// ```ignore
+/// let [before, after] = fields::<u32>("47|53", b"|").array()?;
+/// ```
+pub fn fields<'a, T: ScanInt>(line: &'a str, separators: &'a [u8]) -> Fields<'a, T> {
+    Fields {
+        line,
+        separators,
+        pos: 0,
+        failed: false,
+        _marker: PhantomData,
+    }
+}
+
+/// Zero-copy iterator over the lines of a byte slice. Strips `\r` and skips a trailing empty line.
+#[derive(Debug, Clone)]
+pub struct Lines<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = &'a [u8];
+
+    #[inline]
+    fn next(&mut self) -> Option<&'a [u8]> {
+        if self.bytes.is_empty() {
+            return None;
+        }
+        let (line, rest) = match self.bytes.iter().position(|&b| b == b'\n') {
+            Some(i) => (&self.bytes[..i], &self.bytes[i + 1..]),
+            None => (self.bytes, &[][..]),
+        };
+        self.bytes = rest;
+        Some(line.strip_suffix(b"\r").unwrap_or(line))
+    }
+}
+
+pub fn lines(bytes: &[u8]) -> Lines<'_> {
+    Lines { bytes }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scans_numbers() {
+        assert_eq!(
+            numbers::<u32>(b"3   4\n4   3").collect::<Vec<_>>(),
+            vec![3, 4, 4, 3]
+        );
+        assert_eq!(
+            numbers::<i64>(b"p=0,4 v=-3,12").collect::<Vec<_>>(),
+            vec![0, 4, -3, 12]
+        );
+        assert_eq!(
+            numbers::<u8>(b"1-3: 255").collect::<Vec<_>>(),
+            vec![1, 3, 255]
+        );
+        assert_eq!(numbers::<u32>(b"no digits").next(), None);
+        assert_eq!(
+            numbers::<i8>(b"-128 127").collect::<Vec<_>>(),
+            vec![-128, 127]
+        );
+
+        let mut scan = numbers::<u8>(b"300");
+        assert_eq!(scan.next(), None);
+        assert!(scan.overflowed());
+        let mut scan = numbers::<u8>(b"1 256 2");
+        assert_eq!(scan.by_ref().collect::<Vec<_>>(), vec![1]);
+        assert!(scan.overflowed());
+        assert_eq!(scan.next(), None);
+    }
+
+    #[test]
+    fn scans_fields() {
+        assert_eq!(fields::<u32>("3   4", b" ").array(), Ok([3, 4]));
+        assert_eq!(
+            fields::<i64>("-1,2,,-30", b",").collect::<Result<Vec<_>, _>>(),
+            Ok(vec![-1, 2, -30])
+        );
+
+        let err = fields::<u32>("75,4x7", b",")
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_err();
+        assert_eq!((err.column, err.text.as_str()), (5, "x"));
+        let err = fields::<u8>("1 256 2", b" ").array::<3>().unwrap_err();
+        assert_eq!(
+            (err.column, err.text.as_str(), err.expected.as_str()),
+            (3, "256", "u8")
+        );
+        let err = fields::<u32>("1 2 3", b" ").array::<2>().unwrap_err();
+        assert_eq!((err.column, err.text.as_str()), (5, "3"));
+        let err = fields::<u32>("1", b" ").array::<2>().unwrap_err();
+        assert_eq!((err.column, err.text.as_str()), (2, ""));
+
+        let mut scan = fields::<u64>("190: 10", b": ");
+        assert_eq!(scan.next(), Some(Ok(190)));
+        assert_eq!(scan.position(), 3);
+    }
+
+    #[test]
+    fn splits_lines() {
+        assert_eq!(
+            lines(b"ab\r\ncd\n\nef\n").collect::<Vec<_>>(),
+            vec![&b"ab"[..], b"cd", b"", b"ef"]
+        );
+        assert_eq!(lines(b"").next(), None);
+    }
+}
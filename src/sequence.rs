@@ -0,0 +1,190 @@
+use std::{hash::Hash, ops::RangeInclusive};
+
+use crate::{hashing::FastHashMap, linalg::Rational, math::Integer};
+
+/// Pairwise differences `seq[i + 1] - seq[i]`, widened to `i128` so they never overflow
+/// for inputs of up to 64 bits.
+pub fn differences<T: Integer>(seq: &[T]) -> Vec<i128> {
+    seq.windows(2)
+        .map(|w| w[1].to_i128() - w[0].to_i128())
+        .collect()
+}
+
+fn widen(steps: &RangeInclusive<i64>) -> RangeInclusive<i128> {
+    i128::from(*steps.start())..=i128::from(*steps.end())
+}
+
+/// Checks whether every difference lies in `steps`, or every difference lies in `-steps`.
+/// With `steps = 1..=3` this means "strictly increasing or decreasing by 1 to 3".
+pub fn is_monotonic<T: Integer>(seq: &[T], steps: RangeInclusive<i64>) -> bool {
+    let diffs = differences(seq);
+    let steps = widen(&steps);
+    let descending = -*steps.end()..=-*steps.start();
+    diffs.iter().all(|d| steps.contains(d)) || diffs.iter().all(|d| descending.contains(d))
+}
+
+/// Like [`is_monotonic`], but up to `k` elements may be dropped from the sequence first.
+///
+/// Runs in `O(n * k²)`, i.e. linear for a fixed number of removals.
+pub fn is_monotonic_after_removing<T: Integer>(
+    seq: &[T],
+    steps: RangeInclusive<i64>,
+    k: usize,
+) -> bool {
+    let steps = widen(&steps);
+    let descending = -*steps.end()..=-*steps.start();
+    can_keep_with_removals(seq, &steps, k) || can_keep_with_removals(seq, &descending, k)
+}
+
+fn can_keep_with_removals<T: Integer>(seq: &[T], steps: &RangeInclusive<i128>, k: usize) -> bool {
+    let n = seq.len();
+    if n <= k + 1 {
+        return true;
+    }
+
+    // reachable[i][j]: `seq[i]` can be the last kept element after removing `j` elements before it.
+    let mut reachable = vec![vec![false; k + 1]; n];
+    for (i, row) in reachable.iter_mut().enumerate().take(k + 1) {
+        row[i] = true;
+    }
+
+    for i in 0..n {
+        for j in 0..=k {
+            if !reachable[i][j] {
+                continue;
+            }
+            if j + (n - 1 - i) <= k {
+                return true;
+            }
+            for next in i + 1..=(i + 1 + k - j).min(n - 1) {
+                let diff = seq[next].to_i128() - seq[i].to_i128();
+                if steps.contains(&diff) {
+                    reachable[next][j + next - i - 1] = true;
+                }
+            }
+        }
+    }
+
+    false
+}
+
+/// Predicts the next value by repeatedly taking differences until they are all zero.
+pub fn extrapolate_forward<T: Integer>(seq: &[T]) -> i128 {
+    let mut current: Vec<i128> = seq.iter().map(|n| n.to_i128()).collect();
+    let mut result = 0;
+    while current.iter().any(|&n| n != 0) {
+        result += current[current.len() - 1];
+        current = differences(&current);
+    }
+    result
+}
+
+/// Predicts the value before the first one by repeatedly taking differences.
+pub fn extrapolate_backward<T: Integer>(seq: &[T]) -> i128 {
+    let mut current: Vec<i128> = seq.iter().map(|n| n.to_i128()).collect();
+    let mut firsts = vec![];
+    while current.iter().any(|&n| n != 0) {
+        firsts.push(current[0]);
+        current = differences(&current);
+    }
+    firsts.iter().rev().fold(0, |acc, first| first - acc)
+}
+
+/// Evaluates the unique polynomial through `points` at `x` with Lagrange interpolation.
+pub fn interpolate(points: &[(i64, i64)], x: i64) -> Rational {
+    let x = Rational::from(x);
+    points
+        .iter()
+        .enumerate()
+        .fold(Rational::ZERO, |acc, (i, &(xi, yi))| {
+            let basis = points.iter().enumerate().filter(|&(j, _)| j != i).fold(
+                Rational::ONE,
+                |basis, (_, &(xj, _))| {
+                    basis * (x - Rational::from(xj)) / (Rational::from(xi) - Rational::from(xj))
+                },
+            );
+            acc + basis * Rational::from(yi)
+        })
+}
+
+/// Finds the earliest point after which `seq` repeats at least twice.
+/// Returns `(offset, period)` with the smallest offset and, for it, the smallest period.
+pub fn find_period<T: PartialEq>(seq: &[T]) -> Option<(usize, usize)> {
+    (0..seq.len()).find_map(|offset| {
+        let tail = &seq[offset..];
+        let period = smallest_period(tail);
+        (2 * period <= tail.len()).then_some((offset, period))
+    })
+}
+
+/// Smallest `p` so that `seq[i] == seq[i + p]` for all valid `i`, via the KMP prefix function.
+fn smallest_period<T: PartialEq>(seq: &[T]) -> usize {
+    let mut prefix = vec![0; seq.len()];
+    for i in 1..seq.len() {
+        let mut k = prefix[i - 1];
+        while k > 0 && seq[i] != seq[k] {
+            k = prefix[k - 1];
+        }
+        if seq[i] == seq[k] {
+            k += 1;
+        }
+        prefix[i] = k;
+    }
+    seq.len() - prefix.last().copied().unwrap_or(0)
+}
+
+/// Runs `step` from `initial` until a state repeats.
+/// Returns `(offset, period)`: the state after `offset` steps recurs every `period` steps.
+pub fn detect_cycle<S: Clone + Eq + Hash>(initial: S, step: impl Fn(&S) -> S) -> (usize, usize) {
+    let mut seen = FastHashMap::default();
+    let mut state = initial;
+    let mut i = 0;
+    loop {
+        if let Some(first) = seen.insert(state.clone(), i) {
+            return (first, i - first);
+        }
+        state = step(&state);
+        i += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checks_monotonic_rows() {
+        assert!(is_monotonic(&[7u32, 6, 4, 2, 1], 1..=3));
+        assert!(!is_monotonic(&[1u32, 2, 7, 8, 9], 1..=3));
+        assert!(!is_monotonic(&[1u32, 3, 2, 4, 5], 1..=3));
+        assert!(is_monotonic_after_removing(&[1u32, 3, 2, 4, 5], 1..=3, 1));
+        assert!(is_monotonic_after_removing(&[8u32, 6, 4, 4, 1], 1..=3, 1));
+        assert!(!is_monotonic_after_removing(&[9u32, 7, 6, 2, 1], 1..=3, 1));
+        assert!(is_monotonic_after_removing(&[10u32, 1, 2, 3], 1..=3, 1));
+        assert!(is_monotonic_after_removing(&[1u32, 2, 3, 10], 1..=3, 1));
+        assert!(is_monotonic_after_removing(&[1u32, 9, 9, 2, 3], 1..=3, 2));
+
+        // differences past the i64 range must not wrap around into `steps`
+        assert_eq!(differences(&[i64::MIN, i64::MAX]), vec![u64::MAX as i128]);
+        assert!(!is_monotonic(&[u64::MAX, 0, 1], 1..=3));
+        assert!(!is_monotonic(&[0, u64::MAX - 1, u64::MAX], 1..=3));
+    }
+
+    #[test]
+    fn extrapolates() {
+        assert_eq!(extrapolate_forward(&[0i64, 3, 6, 9, 12, 15]), 18);
+        assert_eq!(extrapolate_forward(&[10i64, 13, 16, 21, 30, 45]), 68);
+        assert_eq!(extrapolate_backward(&[10i64, 13, 16, 21, 30, 45]), 5);
+        assert_eq!(
+            interpolate(&[(0, 10), (1, 13), (2, 16), (3, 21)], 4),
+            Rational::from(30)
+        );
+    }
+
+    #[test]
+    fn finds_periods() {
+        assert_eq!(find_period(&[5, 1, 2, 3, 1, 2, 3, 1]), Some((1, 3)));
+        assert_eq!(find_period(&[1, 2, 3, 4]), None);
+        assert_eq!(detect_cycle(0u32, |n| (n * n + 1) % 7), (3, 1));
+    }
+}
@@ -148,14 +148,19 @@ pub mod child_commands {
                     return None;
                 };
 
+                let timing_str = match parse_threads(l) {
+                    Some(threads) => format!("{timing_str} ({threads} threads)"),
+                    None => timing_str.to_string(),
+                };
+
                 let part = l.split(':').next()?;
                 Some((part, timing_str, nanos))
             })
             .for_each(|(part, timing_str, nanos)| {
                 if part.contains("Part 1") {
-                    timings.part_1 = Some(timing_str.into());
+                    timings.part_1 = Some(timing_str);
                 } else if part.contains("Part 2") {
-                    timings.part_2 = Some(timing_str.into());
+                    timings.part_2 = Some(timing_str);
                 }
 
                 timings.total_nanos += nanos;
@@ -164,6 +169,15 @@ pub mod child_commands {
         timings
     }
 
+    /// Parses the `[N threads]` marker the runner appends to parts that ran in parallel.
+    fn parse_threads(line: &str) -> Option<usize> {
+        line.strip_suffix(" threads]")?
+            .rsplit('[')
+            .next()?
+            .parse()
+            .ok()
+    }
+
     fn parse_to_float(s: &str, postfix: &str) -> Option<f64> {
         s.split(postfix).next()?.parse().ok()
     }
@@ -174,7 +188,7 @@ pub mod child_commands {
             .split(" samples)")
             .next()?
             .split('(')
-            .last()?
+            .next_back()?
             .split('@')
             .next()?
             .trim();
@@ -189,6 +203,34 @@ pub mod child_commands {
         Some((str_timing, parsed_timing))
     }
 
+    #[cfg(test)]
+    mod thread_marker_tests {
+        use super::{parse_exec_time, parse_threads};
+
+        use crate::day;
+
+        #[test]
+        fn parses_thread_markers() {
+            assert_eq!(
+                parse_threads("Part 2: 10 (74.13ms @ 99 samples) [8 threads]"),
+                Some(8)
+            );
+            assert_eq!(parse_threads("Part 1: 0 (74.13ns @ 100000 samples)"), None);
+
+            let res = parse_exec_time(
+                &[
+                    "Part 1: 0 (74.13ns @ 100000 samples)".into(),
+                    "Part 2: 10 (74.13ms @ 99999 samples) [8 threads]".into(),
+                    "".into(),
+                ],
+                day!(1),
+            );
+            assert!((res.total_nanos - 74130074.13_f64).abs() < 1.0e-6);
+            assert_eq!(res.part_1.unwrap(), "74.13ns");
+            assert_eq!(res.part_2.unwrap(), "74.13ms (8 threads)");
+        }
+    }
+
     /// copied from: https://github.com/rust-lang/rust/blob/1.64.0/library/std/src/macros.rs#L328-L333
     #[cfg(feature = "test_lib")]
     macro_rules! assert_approx_eq {
@@ -8,17 +8,70 @@ use std::{cmp, env, process};
 
 use crate::template::ANSI_BOLD;
 use crate::template::{aoc_cli, Day, ANSI_ITALIC, ANSI_RESET};
+use crate::{bisect, memo, parallel};
 
-pub fn run_part<I: Clone, T: Display>(func: impl Fn(I) -> Option<T>, input: I, day: Day, part: u8) {
+/// Return types a solution part may have: an `Option`, or a `Result` whose error is reported
+/// (e.g. a `ParseError` pointing at the offending input).
+pub trait PartResult {
+    type Answer: Display;
+
+    fn answer(&self) -> Option<&Self::Answer>;
+
+    fn error(&self) -> Option<String> {
+        None
+    }
+}
+
+impl<T: Display> PartResult for Option<T> {
+    type Answer = T;
+
+    fn answer(&self) -> Option<&T> {
+        self.as_ref()
+    }
+}
+
+impl<T: Display, E: Display> PartResult for Result<T, E> {
+    type Answer = T;
+
+    fn answer(&self) -> Option<&T> {
+        self.as_ref().ok()
+    }
+
+    fn error(&self) -> Option<String> {
+        self.as_ref().err().map(ToString::to_string)
+    }
+}
+
+pub fn run_part<I: Clone, R: PartResult>(func: impl Fn(I) -> R, input: I, day: Day, part: u8) {
     let part_str = format!("Part {part}");
 
-    let (result, duration, samples) =
-        run_timed(func, input, |result| print_result(result, &part_str, ""));
+    memo::reset_stats();
+    bisect::reset_evaluations();
+    parallel::reset_threads_used();
+
+    let (result, duration, samples) = run_timed(func, input, |result| {
+        print_result(result.answer(), &part_str, "");
+    });
 
-    print_result(&result, &part_str, &format_duration(&duration, samples));
+    let mut duration_str = format_duration(&duration, samples);
 
-    if let Some(result) = result {
-        submit_result(result, day, part);
+    // flag multi-threaded parts so their timings are not mistaken for single-threaded ones.
+    let threads = parallel::threads_used();
+    if threads > 1 {
+        duration_str.push_str(&format!(" [{threads} threads]"));
+    }
+
+    print_result(result.answer(), &part_str, &duration_str);
+
+    if let Some(error) = result.error() {
+        eprintln!("{error}");
+    }
+
+    #[cfg(debug_assertions)]
+    print_debug_stats();
+
+    if let Some(answer) = result.answer() {
+        submit_result(answer, day, part);
     }
 }
 
@@ -94,7 +147,7 @@ fn format_duration(duration: &Duration, samples: u128) -> String {
     }
 }
 
-fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str) {
+fn print_result<T: Display>(result: Option<&T>, part: &str, duration_str: &str) {
     let is_intermediate_result = duration_str.is_empty();
 
     match result {
@@ -129,6 +182,21 @@ fn print_result<T: Display>(result: &Option<T>, part: &str, duration_str: &str)
     }
 }
 
+/// Print cache statistics of any `Memo` and the number of `bisect` predicate evaluations of the part.
+/// These are only tracked in debug builds.
+#[cfg(debug_assertions)]
+fn print_debug_stats() {
+    let (hits, misses) = memo::stats();
+    if hits + misses > 0 {
+        println!(" > {ANSI_ITALIC}memo: {hits} hits, {misses} misses{ANSI_RESET}");
+    }
+
+    let evaluations = bisect::evaluations();
+    if evaluations > 0 {
+        println!(" > {ANSI_ITALIC}bisect: {evaluations} predicate evaluations{ANSI_RESET}");
+    }
+}
+
 /// Parse the arguments passed to `solve` and try to submit one part of the solution if:
 ///  1. we are in `--release` mode.
 ///  2. aoc-cli is installed.
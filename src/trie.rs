@@ -0,0 +1,275 @@
+use std::collections::VecDeque;
+
+use crate::hashing::FastHashMap;
+
+#[derive(Debug, Clone, Default)]
+struct Node {
+    children: FastHashMap<u8, usize>,
+    pattern: Option<usize>,
+}
+
+/// A prefix tree over the bytes of a set of patterns. Patterns are identified by their insertion index.
+#[derive(Debug, Clone)]
+pub struct Trie {
+    nodes: Vec<Node>,
+    patterns: usize,
+}
+
+impl Trie {
+    pub fn new() -> Self {
+        Self {
+            nodes: vec![Node::default()],
+            patterns: 0,
+        }
+    }
+
+    /// Adds `pattern` and returns its id. Inserting a pattern twice returns the first id.
+    pub fn insert(&mut self, pattern: &str) -> usize {
+        let mut node = 0;
+        for &b in pattern.as_bytes() {
+            node = match self.nodes[node].children.get(&b) {
+                Some(&next) => next,
+                None => {
+                    self.nodes.push(Node::default());
+                    let next = self.nodes.len() - 1;
+                    self.nodes[node].children.insert(b, next);
+                    next
+                }
+            };
+        }
+
+        *self.nodes[node].pattern.get_or_insert_with(|| {
+            self.patterns += 1;
+            self.patterns - 1
+        })
+    }
+
+    /// Number of distinct patterns.
+    pub fn len(&self) -> usize {
+        self.patterns
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns == 0
+    }
+
+    fn walk(&self, bytes: &[u8]) -> Option<usize> {
+        bytes
+            .iter()
+            .try_fold(0, |node, b| self.nodes[node].children.get(b).copied())
+    }
+
+    /// Id of `word` if it is one of the patterns.
+    pub fn get(&self, word: &str) -> Option<usize> {
+        self.nodes[self.walk(word.as_bytes())?].pattern
+    }
+
+    pub fn contains(&self, word: &str) -> bool {
+        self.get(word).is_some()
+    }
+
+    /// Whether any pattern starts with `prefix`.
+    pub fn has_prefix(&self, prefix: &str) -> bool {
+        self.walk(prefix.as_bytes()).is_some()
+    }
+
+    /// `(length, id)` of every pattern that `text` starts with, shortest first.
+    pub fn prefixes<'a>(&'a self, text: &'a str) -> impl Iterator<Item = (usize, usize)> + 'a {
+        let bytes = text.as_bytes();
+        let mut node = Some(0);
+        (0..=bytes.len()).filter_map(move |len| {
+            let current = node?;
+            node = bytes
+                .get(len)
+                .and_then(|b| self.nodes[current].children.get(b).copied());
+            self.nodes[current].pattern.map(|id| (len, id))
+        })
+    }
+
+    /// Number of ways `text` can be split into a sequence of patterns (each usable any number of times).
+    pub fn count_decompositions(&self, text: &str) -> u64 {
+        // ways[i] counts the splits of text[..i]
+        let mut ways = vec![0u64; text.len() + 1];
+        ways[0] = 1;
+        for start in 0..text.len() {
+            if ways[start] == 0 {
+                continue;
+            }
+            for (len, _) in self.prefixes(&text[start..]).filter(|(len, _)| *len > 0) {
+                ways[start + len] += ways[start];
+            }
+        }
+        ways[text.len()]
+    }
+}
+
+impl Default for Trie {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a> FromIterator<&'a str> for Trie {
+    fn from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> Self {
+        let mut trie = Self::new();
+        for pattern in iter {
+            trie.insert(pattern);
+        }
+        trie
+    }
+}
+
+/// An occurrence of pattern `pattern` at `text[start..end]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Match {
+    pub pattern: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Finds every occurrence of many patterns in a single pass over the text (Aho–Corasick).
+/// Pattern ids are the same as those of the [`Trie`] it is built from.
+#[derive(Debug, Clone)]
+pub struct Matcher {
+    trie: Trie,
+    fail: Vec<usize>,
+    // every (pattern id, length) ending at a node, including those reached through fail links
+    outputs: Vec<Vec<(usize, usize)>>,
+}
+
+impl Matcher {
+    pub fn new(trie: Trie) -> Self {
+        let nodes = &trie.nodes;
+        let mut fail = vec![0; nodes.len()];
+        let mut depth = vec![0; nodes.len()];
+        let mut outputs = vec![vec![]; nodes.len()];
+        if let Some(id) = nodes[0].pattern {
+            outputs[0].push((id, 0));
+        }
+
+        let mut queue = nodes[0].children.values().copied().collect::<VecDeque<_>>();
+        for &child in &queue {
+            depth[child] = 1;
+        }
+        while let Some(node) = queue.pop_front() {
+            let mut out = nodes[node]
+                .pattern
+                .map(|id| vec![(id, depth[node])])
+                .unwrap_or_default();
+            out.extend(outputs[fail[node]].iter().copied());
+            outputs[node] = out;
+
+            for (b, &child) in &nodes[node].children {
+                let mut f = fail[node];
+                fail[child] = loop {
+                    if let Some(&next) = nodes[f].children.get(b) {
+                        break next;
+                    }
+                    if f == 0 {
+                        break 0;
+                    }
+                    f = fail[f];
+                };
+                depth[child] = depth[node] + 1;
+                queue.push_back(child);
+            }
+        }
+
+        Self {
+            trie,
+            fail,
+            outputs,
+        }
+    }
+
+    pub fn trie(&self) -> &Trie {
+        &self.trie
+    }
+
+    /// All occurrences of all patterns, overlapping ones included, ordered by where they end.
+    pub fn find_all(&self, text: &str) -> Vec<Match> {
+        let mut matches = vec![];
+        let mut node = 0;
+        for (i, b) in text.bytes().enumerate() {
+            node = loop {
+                if let Some(&next) = self.trie.nodes[node].children.get(&b) {
+                    break next;
+                }
+                if node == 0 {
+                    break 0;
+                }
+                node = self.fail[node];
+            };
+            matches.extend(self.outputs[node].iter().map(|&(pattern, len)| Match {
+                pattern,
+                start: i + 1 - len,
+                end: i + 1,
+            }));
+        }
+        matches
+    }
+
+    /// Number of occurrences of each pattern, indexed by pattern id.
+    pub fn count(&self, text: &str) -> Vec<usize> {
+        let mut counts = vec![0; self.trie.len()];
+        for m in self.find_all(text) {
+            counts[m.pattern] += 1;
+        }
+        counts
+    }
+}
+
+impl<'a> FromIterator<&'a str> for Matcher {
+    fn from_iter<I: IntoIterator<Item = &'a str>>(iter: I) -> Self {
+        Self::new(Trie::from_iter(iter))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_prefixes() {
+        let trie = Trie::from_iter(["a", "ab", "abc", "b"]);
+        assert_eq!(trie.len(), 4);
+        assert!(trie.contains("ab"));
+        assert!(!trie.contains("bc"));
+        assert!(trie.has_prefix("abc"));
+        assert!(!trie.has_prefix("bc"));
+        assert_eq!(
+            trie.prefixes("abcd").collect::<Vec<_>>(),
+            vec![(1, 0), (2, 1), (3, 2)]
+        );
+    }
+
+    #[test]
+    fn counts_decompositions() {
+        // towels from 2024 day 19
+        let towels = Trie::from_iter(["r", "wr", "b", "g", "bwu", "rb", "gb", "br"]);
+        let designs = [
+            "brwrr", "bggr", "gbbr", "rrbgbr", "ubwu", "bwurrg", "brgr", "bbrgwb",
+        ];
+        let ways = designs
+            .iter()
+            .map(|d| towels.count_decompositions(d))
+            .collect::<Vec<_>>();
+        assert_eq!(ways, vec![2, 1, 4, 6, 0, 1, 2, 0]);
+    }
+
+    #[test]
+    fn finds_all_patterns() {
+        let matcher = Matcher::from_iter(["he", "she", "his", "hers"]);
+        let found = matcher
+            .find_all("ushers")
+            .into_iter()
+            .map(|m| (m.pattern, m.start, m.end))
+            .collect::<Vec<_>>();
+        assert_eq!(found, vec![(1, 1, 4), (0, 2, 4), (3, 2, 6)]);
+
+        let matcher = Matcher::from_iter(["XMAS", "SAMX"]);
+        assert_eq!(matcher.count("XMASAMXMAS"), vec![2, 1]);
+        let matcher = Matcher::from_iter(["aa"]);
+        assert_eq!(matcher.find_all("aaaa").len(), 3);
+    }
+}